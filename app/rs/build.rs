@@ -0,0 +1,43 @@
+/*
+ * Host (Linux) builds exist only to run the tests, which start a desktop JVM
+ * through jni's `invocation` feature. That feature links libjvm.so but does
+ * not tell the loader where to find it, so record its directory as an rpath.
+ */
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=JAVA_HOME");
+    if env::var("CARGO_CFG_TARGET_OS").as_deref() != Ok("linux") {
+        return;
+    }
+    match java_home().as_deref().and_then(libjvm_dir) {
+        Some(dir) => println!("cargo:rustc-link-arg=-Wl,-rpath,{}", dir.display()),
+        None => println!("cargo:warning=libjvm.so not found; set JAVA_HOME to run the JVM tests"),
+    }
+}
+
+fn java_home() -> Option<PathBuf> {
+    if let Ok(home) = env::var("JAVA_HOME") {
+        return Some(PathBuf::from(home));
+    }
+    // `java -XshowSettings:properties -version` prints `java.home = ...` to stderr.
+    let output = Command::new("java").args(["-XshowSettings:properties", "-version"]).output().ok()?;
+    let settings = String::from_utf8_lossy(&output.stderr);
+    let line = settings.lines().find(|line| line.trim_start().starts_with("java.home"))?;
+    Some(PathBuf::from(line.split_once('=')?.1.trim()))
+}
+
+/// `lib/server` on JDK 9+, `jre/lib/<arch>/server` on JDK 8.
+fn libjvm_dir(home: &Path) -> Option<PathBuf> {
+    let mut candidates = vec![home.join("lib/server")];
+    for jre_lib in [home.join("jre/lib"), home.join("lib")] {
+        if let Ok(entries) = jre_lib.read_dir() {
+            candidates.extend(entries.flatten().map(|entry| entry.path().join("server")));
+        }
+    }
+    candidates.into_iter().find(|dir| dir.join("libjvm.so").is_file())
+}
@@ -0,0 +1,61 @@
+/*
+ * Command-line glob query over the parameter registry. Prints the matching
+ * keys in getprop's `[name]: [value]` format; values are only filled in when
+ * run on a device, where the system properties exist.
+ *
+ * With --dump, prints every property in a copied /dev/__properties__ instead,
+ * and with --persistent, the persist.* properties in a copied /data/property.
+ */
+
+use modify_mac::persistent_properties::PersistentProperties;
+use modify_mac::{prop_area, props, registry};
+use std::env;
+use std::path::Path;
+use std::process;
+
+const USAGE: &str = "usage: props-query <pattern>...   e.g. props-query 'ro.build.version.*'
+       props-query --dump <copy of /dev/__properties__>
+       props-query --persistent <copy of /data/property>";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.as_slice() {
+        [] => {
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+        [flag, dir] if flag == "--dump" => match prop_area::dump(Path::new(dir)) {
+            Ok(properties) => {
+                for property in properties {
+                    let context = property.context.as_deref().unwrap_or("-");
+                    println!("[{}]: [{}] {}", property.name, property.value, context);
+                }
+            }
+            Err(e) => {
+                eprintln!("{}: {}", dir, e);
+                process::exit(1);
+            }
+        },
+        [flag, dir] if flag == "--persistent" => match PersistentProperties::load(Path::new(dir)) {
+            Ok(persistent) => {
+                for (name, value) in &persistent.properties {
+                    println!("[{}]: [{}]", name, value);
+                }
+                for reason in &persistent.rejected {
+                    eprintln!("skipped: {}", reason);
+                }
+            }
+            Err(e) => {
+                eprintln!("{}: {}", dir, e);
+                process::exit(1);
+            }
+        },
+        patterns => {
+            for pattern in patterns {
+                for key in registry::matching(pattern) {
+                    println!("[{}]: [{}]", key, props::get(&key).unwrap_or_default());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,414 @@
+/*
+ * Build date behind ro.build.date and its variants, ro.system.build.date and
+ * Build.TIME: every vendor format parsed, normalised to one timestamp and
+ * checked for agreement.
+ */
+
+use jni::errors::Result;
+use jni::JNIEnv;
+use serde::Serialize;
+
+use crate::props;
+use crate::time;
+
+/// The properties read, in the order they are reported.
+pub const KEYS: [&str; 8] = [
+    "ro.build.date.utc",
+    "ro.build.date",
+    "ro.build.date.Ymd",
+    "ro.build.date.ymd",
+    "ro.build.date.YmdHM",
+    "ro.system.build.date.utc",
+    "ro.system.build.date",
+    "ro.vendor.build.date.utc",
+];
+
+/// Offsets a build machine can plausibly be in, for sources without one.
+const MIN_OFFSET_S: i64 = -12 * 3600;
+const MAX_OFFSET_S: i64 = 14 * 3600;
+const OFFSET_STEP_S: i64 = 15 * 60;
+/// The dates accepted: 1970 to 9999, the last year ISO-8601 writes in four
+/// digits. Anything else is a corrupt property, and would overflow the
+/// conversions to milliseconds.
+const YEARS: std::ops::RangeInclusive<i64> = 1970..=9999;
+const MAX_EPOCH_S: i64 = 253_402_300_799;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Precision {
+    Millisecond,
+    Second,
+    Minute,
+    Day,
+}
+
+impl Precision {
+    fn seconds(self) -> i64 {
+        match self {
+            Precision::Millisecond | Precision::Second => 1,
+            Precision::Minute => 60,
+            Precision::Day => 86_400,
+        }
+    }
+}
+
+/// A parsed date. `offset_s` is `None` for local times whose zone is not
+/// known, such as `ro.build.date.YmdHM` or `date` output in `CST`, which is
+/// China Standard Time on Chinese builds but US Central elsewhere.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ParsedDate {
+    /// Seconds since the epoch of the wall-clock time as written.
+    pub local_s: i64,
+    pub offset_s: Option<i64>,
+    pub precision: Precision,
+}
+
+impl ParsedDate {
+    /// Milliseconds since the epoch, when the zone is known.
+    pub fn epoch_ms(&self) -> Option<i64> {
+        self.local_s.checked_sub(self.offset_s?)?.checked_mul(1000)
+    }
+
+    /// Whether this date is `epoch_ms` written at this precision in the
+    /// known zone or, if the zone is unknown, in any plausible one.
+    pub fn matches(&self, epoch_ms: i64) -> bool {
+        let epoch_s = epoch_ms.div_euclid(1000);
+        let step = self.precision.seconds();
+        let truncate = |s: i64| s - s.rem_euclid(step);
+        match self.offset_s {
+            Some(offset) => truncate(epoch_s + offset) == self.local_s,
+            None => (MIN_OFFSET_S..=MAX_OFFSET_S)
+                .step_by(OFFSET_STEP_S as usize)
+                .any(|offset| truncate(epoch_s + offset) == self.local_s),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DateSource {
+    pub key: String,
+    pub raw: String,
+    /// `None` when the value is in no known format.
+    pub parsed: Option<ParsedDate>,
+    /// The parsed value in ISO-8601, with the zone dropped when unknown.
+    pub normalized: Option<String>,
+    /// False when the value parsed but does not describe `build_time_ms`.
+    pub agrees: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BuildDateReport {
+    /// `Build.TIME` when available, otherwise the first source with a known zone.
+    pub build_time_ms: Option<i64>,
+    pub build_time: Option<String>,
+    pub sources: Vec<DateSource>,
+    /// Keys of the sources that parsed but disagree with `build_time_ms`.
+    pub disagreements: Vec<String>,
+}
+
+impl BuildDateReport {
+    pub fn current(env: &JNIEnv) -> Result<BuildDateReport> {
+        let build_time = env.get_static_field("android/os/Build", "TIME", "J")?.j()?;
+        Ok(BuildDateReport::read(Some(build_time)))
+    }
+
+    /// The properties, plus `Build.TIME` when the caller has it.
+    pub fn read(build_time_ms: Option<i64>) -> BuildDateReport {
+        let mut values: Vec<(String, String)> = Vec::new();
+        if let Some(build_time_ms) = build_time_ms {
+            values.push(("Build.TIME".to_string(), build_time_ms.to_string()));
+        }
+        for key in KEYS {
+            if let Some(value) = props::get(key) {
+                values.push((key.to_string(), value));
+            }
+        }
+        BuildDateReport::new(values)
+    }
+
+    /// Parses `(key, raw value)` pairs, with `Build.TIME` as the reference
+    /// when present.
+    pub fn new(values: Vec<(String, String)>) -> BuildDateReport {
+        let parsed: Vec<Option<ParsedDate>> = values.iter().map(|(key, raw)| parse(key, raw)).collect();
+        let build_time_ms = parsed.iter().find_map(|date| date.and_then(|date| date.epoch_ms()));
+
+        let mut report = BuildDateReport {
+            build_time_ms,
+            build_time: build_time_ms.map(time::format_iso8601),
+            sources: Vec::new(),
+            disagreements: Vec::new(),
+        };
+        for ((key, raw), parsed) in values.into_iter().zip(parsed) {
+            let agrees = match (parsed, build_time_ms) {
+                (Some(parsed), Some(reference)) => parsed.matches(reference),
+                _ => true,
+            };
+            if !agrees {
+                report.disagreements.push(key.clone());
+            }
+            report.sources.push(DateSource { key, raw, parsed, normalized: parsed.map(|date| normalize(&date)), agrees });
+        }
+        report
+    }
+}
+
+/// Parses `raw` in the format `key` is known to use.
+pub fn parse(key: &str, raw: &str) -> Option<ParsedDate> {
+    let raw = raw.trim();
+    if key == "Build.TIME" {
+        let ms: i64 = raw.parse().ok()?;
+        // Build.TIME is 0 when ro.build.date.utc was missing.
+        let date = ParsedDate { local_s: ms.div_euclid(1000), offset_s: Some(0), precision: Precision::Millisecond };
+        return (ms > 0 && date.local_s <= MAX_EPOCH_S).then_some(date);
+    }
+    if key.ends_with(".utc") {
+        let seconds: i64 = raw.parse().ok()?;
+        let date = ParsedDate { local_s: seconds, offset_s: Some(0), precision: Precision::Second };
+        return (0..=MAX_EPOCH_S).contains(&seconds).then_some(date);
+    }
+    match key.rsplit('.').next()? {
+        "Ymd" | "ymd" | "YmdHM" => parse_digits(raw),
+        _ => parse_date_output(raw),
+    }
+}
+
+/// `20220211`, `220211` or `202202111845`.
+pub fn parse_digits(raw: &str) -> Option<ParsedDate> {
+    if !raw.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let field = |range: std::ops::Range<usize>| raw[range].parse::<u32>().ok();
+    let (year, month, day, minutes, precision) = match raw.len() {
+        6 => (2000 + field(0..2)? as i64, field(2..4)?, field(4..6)?, 0, Precision::Day),
+        8 => (field(0..4)? as i64, field(4..6)?, field(6..8)?, 0, Precision::Day),
+        12 => {
+            let (hour, minute) = (field(8..10)?, field(10..12)?);
+            if hour > 23 || minute > 59 {
+                return None;
+            }
+            (field(0..4)? as i64, field(4..6)?, field(6..8)?, hour * 60 + minute, Precision::Minute)
+        }
+        _ => return None,
+    };
+    let days = civil_days(year, month, day)?;
+    Some(ParsedDate { local_s: days * 86_400 + minutes as i64 * 60, offset_s: None, precision })
+}
+
+/// `date` output as the build writes it: `Fri Feb 11 18:45:22 CST 2022`,
+/// `Wed Mar  2 22:09:51 +08 2022`, the zh_CN form
+/// `2022年 02月 11日 星期五 18:45:22 CST`, or `2022-02-11 18:45:22`.
+pub fn parse_date_output(raw: &str) -> Option<ParsedDate> {
+    let (mut year, mut month, mut day, mut clock) = (None, None, None, None);
+    let mut offset_s = None;
+    for token in raw.split_whitespace() {
+        if let Some(value) = token.strip_suffix('年') {
+            year = Some(value.parse().ok()?);
+        } else if let Some(value) = token.strip_suffix('月') {
+            month = Some(value.parse().ok()?);
+        } else if let Some(value) = token.strip_suffix('日') {
+            day = Some(value.parse().ok()?);
+        } else if token.contains(':') && token.starts_with(|c: char| c.is_ascii_digit()) {
+            clock = Some(parse_clock(token)?);
+        } else if token.len() == 10 && token.as_bytes()[4] == b'-' {
+            let mut parts = token.split('-');
+            year = Some(parts.next()?.parse().ok()?);
+            month = Some(parts.next()?.parse().ok()?);
+            day = Some(parts.next()?.parse().ok()?);
+        } else if let Some(number) = token.parse::<u32>().ok().filter(|_| token.bytes().all(|b| b.is_ascii_digit())) {
+            match token.len() {
+                4 => year = Some(number as i64),
+                1 | 2 => day = Some(number),
+                _ => return None,
+            }
+        } else if let Some(value) = month_number(token) {
+            month = Some(value);
+        } else if let Some(value) = zone_offset(token) {
+            offset_s = Some(value);
+        }
+        // Weekdays, 星期五 and unknown zone abbreviations are ignored.
+    }
+    let days = civil_days(year?, month?, day?)?;
+    Some(ParsedDate { local_s: days * 86_400 + clock? as i64, offset_s, precision: Precision::Second })
+}
+
+/// The ISO-8601 form: `Z`-terminated when the zone is known, bare local time otherwise.
+pub fn normalize(date: &ParsedDate) -> String {
+    match (date.epoch_ms(), date.precision) {
+        (Some(ms), _) => time::format_iso8601(ms),
+        (None, Precision::Day) => time::format_iso8601(date.local_s * 1000)[..10].to_string(),
+        (None, Precision::Minute) => time::format_iso8601(date.local_s * 1000)[..16].to_string(),
+        (None, _) => time::format_iso8601(date.local_s * 1000)[..19].to_string(),
+    }
+}
+
+fn civil_days(year: i64, month: u32, day: u32) -> Option<i64> {
+    if !YEARS.contains(&year) || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let days = time::days_from_civil(year, month, day);
+    // Rejects e.g. Feb 30, which would otherwise roll over into March.
+    (time::civil_from_days(days) == (year, month, day)).then_some(days)
+}
+
+/// `HH:MM:SS` as seconds into the day.
+fn parse_clock(token: &str) -> Option<u32> {
+    let mut parts = token.split(':').map(|part| part.parse::<u32>().ok());
+    let (hour, minute, second) = (parts.next()??, parts.next()??, parts.next().unwrap_or(Some(0))?);
+    if parts.next().is_some() || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+    Some(hour * 3600 + minute * 60 + second)
+}
+
+fn month_number(token: &str) -> Option<u32> {
+    const MONTHS: [&str; 12] = ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+    if token.len() < 3 || !token.is_ascii() {
+        return None;
+    }
+    let prefix = token[..3].to_ascii_lowercase();
+    MONTHS.iter().position(|month| *month == prefix).map(|index| index as u32 + 1)
+}
+
+/// `UTC`, `GMT`, `Z`, `+08`, `+0800`, `+08:00`, `GMT+8` and the like. Other
+/// abbreviations are ambiguous (`CST`, `IST`) or too many to list.
+fn zone_offset(token: &str) -> Option<i64> {
+    let upper = token.to_ascii_uppercase();
+    if matches!(upper.as_str(), "UTC" | "GMT" | "Z") {
+        return Some(0);
+    }
+    let numeric = upper.strip_prefix("UTC").or_else(|| upper.strip_prefix("GMT")).unwrap_or(&upper);
+    let sign = match numeric.as_bytes().first()? {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return None,
+    };
+    let digits: String = numeric[1..].chars().filter(|c| *c != ':').collect();
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let (hours, minutes) = match digits.len() {
+        1 | 2 => (digits.parse::<i64>().ok()?, 0),
+        4 => (digits[..2].parse::<i64>().ok()?, digits[2..].parse::<i64>().ok()?),
+        _ => return None,
+    };
+    Some(sign * (hours * 3600 + minutes * 60))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect()
+    }
+
+    #[test]
+    fn parses_aosp_date_output() {
+        let date = parse("ro.build.date", "Mon Dec 13 20:42:33 UTC 2021").unwrap();
+        assert_eq!(date.epoch_ms(), Some(1_639_428_153_000));
+        assert_eq!(normalize(&date), "2021-12-13T20:42:33.000Z");
+    }
+
+    #[test]
+    fn parses_numeric_offsets_and_padded_days() {
+        let date = parse_date_output("Wed Mar  2 22:09:51 +08 2022").unwrap();
+        assert_eq!(date.offset_s, Some(8 * 3600));
+        assert_eq!(normalize(&date), "2022-03-02T14:09:51.000Z");
+        assert_eq!(parse_date_output("Wed Mar 2 22:09:51 GMT+05:30 2022").unwrap().offset_s, Some(19_800));
+    }
+
+    #[test]
+    fn ambiguous_zones_stay_local() {
+        let date = parse("ro.build.date", "Thu Dec 23 05:03:49 CST 2021").unwrap();
+        assert_eq!(date.offset_s, None);
+        assert_eq!(normalize(&date), "2021-12-23T05:03:49");
+        // China Standard Time, as on Xiaomi builds.
+        assert!(date.matches(1_640_207_029_000));
+    }
+
+    #[test]
+    fn parses_zh_cn_date_output() {
+        let date = parse_date_output("2022年 02月 11日 星期五 18:45:22 CST").unwrap();
+        assert_eq!(normalize(&date), "2022-02-11T18:45:22");
+        assert!(date.matches(1_644_576_322_000));
+    }
+
+    #[test]
+    fn parses_digit_formats() {
+        assert_eq!(normalize(&parse("ro.build.date.Ymd", "20220211").unwrap()), "2022-02-11");
+        assert_eq!(normalize(&parse("ro.build.date.ymd", "220211").unwrap()), "2022-02-11");
+        let date = parse("ro.build.date.YmdHM", "202202111845").unwrap();
+        assert_eq!((date.precision, normalize(&date)), (Precision::Minute, "2022-02-11T18:45".to_string()));
+        assert_eq!(parse("ro.build.date.Ymd", "20220230"), None);
+        assert_eq!(parse("ro.build.date.YmdHM", "202202112460"), None);
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        assert_eq!(parse("ro.build.date", "unknown"), None);
+        assert_eq!(parse("ro.build.date.utc", "Fri Feb 11"), None);
+        assert_eq!(parse("Build.TIME", "0"), None);
+    }
+
+    #[test]
+    fn rejects_out_of_range_dates() {
+        assert_eq!(parse("ro.build.date.utc", "99999999999999999"), None);
+        assert_eq!(parse("ro.build.date.utc", &i64::MIN.to_string()), None);
+        assert_eq!(parse("ro.build.date.utc", "-1"), None);
+        assert_eq!(parse("Build.TIME", &i64::MAX.to_string()), None);
+        assert_eq!(parse("ro.build.date", "99999999999999年 02月 11日 星期五 18:45:22 CST"), None);
+        assert_eq!(parse("ro.build.date", "-9223372036854775808年 02月 11日 18:45:22"), None);
+        assert_eq!(parse("ro.build.date", "1969-12-31 23:59:59"), None);
+        let last = parse("ro.build.date.utc", "253402300799").unwrap();
+        assert_eq!(normalize(&last), "9999-12-31T23:59:59.000Z");
+        assert_eq!(ParsedDate { local_s: i64::MIN, offset_s: Some(1), precision: Precision::Second }.epoch_ms(), None);
+
+        let report = BuildDateReport::new(values(&[
+            ("ro.build.date.utc", "99999999999999999"),
+            ("ro.build.date", "Fri Feb 11 18:45:22 UTC 2022"),
+        ]));
+        assert_eq!(report.build_time.as_deref(), Some("2022-02-11T18:45:22.000Z"));
+        assert_eq!((report.sources[0].parsed, report.sources[0].agrees), (None, true));
+    }
+
+    #[test]
+    fn samsung_sources_agree() {
+        let report = BuildDateReport::new(values(&[
+            ("Build.TIME", "1644572722000"),
+            ("ro.build.date.utc", "1644572722"),
+            ("ro.build.date", "Fri Feb 11 18:45:22 KST 2022"),
+            ("ro.system.build.date.utc", "1644572722"),
+            ("ro.system.build.date", "Fri Feb 11 18:45:22 KST 2022"),
+        ]));
+        assert_eq!(report.build_time.as_deref(), Some("2022-02-11T09:45:22.000Z"));
+        assert_eq!(report.disagreements, Vec::<String>::new());
+    }
+
+    #[test]
+    fn oppo_style_sources_agree() {
+        let report = BuildDateReport::new(values(&[
+            ("ro.build.date.utc", "1644576322"),
+            ("ro.build.date", "2022年 02月 11日 星期五 18:45:22 CST"),
+            ("ro.build.date.Ymd", "20220211"),
+            ("ro.build.date.ymd", "220211"),
+            ("ro.build.date.YmdHM", "202202111845"),
+        ]));
+        assert_eq!(report.build_time_ms, Some(1_644_576_322_000));
+        assert_eq!(report.disagreements, Vec::<String>::new());
+    }
+
+    #[test]
+    fn reports_disagreeing_sources() {
+        let report = BuildDateReport::new(values(&[
+            ("Build.TIME", "1644572722000"),
+            ("ro.build.date.utc", "1644572722"),
+            ("ro.build.date", "Fri Feb 11 18:45:22 UTC 2022"),
+            ("ro.build.date.Ymd", "20220101"),
+            ("ro.system.build.date.utc", "1640207029"),
+            ("ro.build.date.YmdHM", "garbage"),
+        ]));
+        assert_eq!(report.disagreements, ["ro.build.date", "ro.build.date.Ymd", "ro.system.build.date.utc"]);
+        let garbage = report.sources.last().unwrap();
+        assert_eq!((garbage.parsed, garbage.agrees), (None, true));
+    }
+}
@@ -0,0 +1,148 @@
+/*
+ * Shell-command-shaped parameters: getprop <name>, settings get
+ * system|secure|global <name>, uname [-a|-r|-m] and pm list packages
+ * [-s|-3], parsed and routed to the matching backend.
+ */
+
+use jni::JNIEnv;
+use serde::Serialize;
+use std::error::Error;
+use std::fmt;
+
+use crate::context::{self, Namespace};
+use crate::kernel::Uname;
+use crate::packages::{PackageFilter, PackageList};
+use crate::props;
+
+/// Which field `uname` prints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnameField {
+    /// No flag: the kernel name.
+    Sysname,
+    /// `-a`
+    All,
+    /// `-r`
+    Release,
+    /// `-m`
+    Machine,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Getprop(String),
+    SettingsGet(Namespace, String),
+    Uname(UnameField),
+    ListPackages(PackageFilter),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The text does not start with a supported command.
+    NotACommand,
+    /// A supported command with the wrong arguments.
+    Usage(&'static str),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::NotACommand => write!(f, "not a supported command"),
+            ParseError::Usage(usage) => write!(f, "usage: {}", usage),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// What a command printed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum Output {
+    /// A single value, `None` when the property or setting is unset.
+    Value(Option<String>),
+    Packages(PackageList),
+}
+
+impl Command {
+    pub fn parse(text: &str) -> Result<Command, ParseError> {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        match words.as_slice() {
+            ["getprop", name] => Ok(Command::Getprop(name.to_string())),
+            ["getprop", ..] => Err(ParseError::Usage("getprop <name>")),
+            ["settings", "get", namespace, name] => {
+                let namespace = match *namespace {
+                    "system" => Namespace::System,
+                    "secure" => Namespace::Secure,
+                    "global" => Namespace::Global,
+                    _ => return Err(ParseError::Usage("settings get system|secure|global <name>")),
+                };
+                Ok(Command::SettingsGet(namespace, name.to_string()))
+            }
+            ["settings", ..] => Err(ParseError::Usage("settings get system|secure|global <name>")),
+            ["uname"] => Ok(Command::Uname(UnameField::Sysname)),
+            ["uname", "-a"] => Ok(Command::Uname(UnameField::All)),
+            ["uname", "-r"] => Ok(Command::Uname(UnameField::Release)),
+            ["uname", "-m"] => Ok(Command::Uname(UnameField::Machine)),
+            ["uname", ..] => Err(ParseError::Usage("uname [-a|-r|-m]")),
+            ["pm", "list", "packages"] => Ok(Command::ListPackages(PackageFilter::All)),
+            ["pm", "list", "packages", "-s"] => Ok(Command::ListPackages(PackageFilter::System)),
+            ["pm", "list", "packages", "-3"] => Ok(Command::ListPackages(PackageFilter::ThirdParty)),
+            ["pm", ..] => Err(ParseError::Usage("pm list packages [-s|-3]")),
+            _ => Err(ParseError::NotACommand),
+        }
+    }
+
+    pub fn run(&self, env: &JNIEnv) -> Result<Output, Box<dyn Error>> {
+        Ok(match self {
+            Command::Getprop(name) => Output::Value(props::get(name)),
+            Command::SettingsGet(namespace, name) => {
+                Output::Value(context::settings_string(env, context::application_context(env)?, *namespace, name)?)
+            }
+            Command::Uname(field) => {
+                let uname = Uname::read()?;
+                Output::Value(Some(match field {
+                    UnameField::Sysname => uname.sysname,
+                    UnameField::All => uname.all(),
+                    UnameField::Release => uname.release,
+                    UnameField::Machine => uname.machine,
+                }))
+            }
+            Command::ListPackages(filter) => Output::Packages(PackageList::current(env, *filter)?),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_supported_commands() {
+        assert_eq!(Command::parse("getprop ro.build.version.sdk"), Ok(Command::Getprop("ro.build.version.sdk".into())));
+        assert_eq!(
+            Command::parse("  settings get global  adb_enabled "),
+            Ok(Command::SettingsGet(Namespace::Global, "adb_enabled".into()))
+        );
+        assert_eq!(Command::parse("uname"), Ok(Command::Uname(UnameField::Sysname)));
+        assert_eq!(Command::parse("uname -m"), Ok(Command::Uname(UnameField::Machine)));
+        assert_eq!(Command::parse("pm list packages"), Ok(Command::ListPackages(PackageFilter::All)));
+        assert_eq!(Command::parse("pm list packages -3"), Ok(Command::ListPackages(PackageFilter::ThirdParty)));
+    }
+
+    #[test]
+    fn rejects_bad_arguments_with_usage() {
+        assert_eq!(Command::parse("getprop"), Err(ParseError::Usage("getprop <name>")));
+        assert_eq!(Command::parse("getprop a b"), Err(ParseError::Usage("getprop <name>")));
+        assert!(matches!(Command::parse("settings get user android_id"), Err(ParseError::Usage(_))));
+        assert!(matches!(Command::parse("settings put secure android_id 1"), Err(ParseError::Usage(_))));
+        assert!(matches!(Command::parse("uname -x"), Err(ParseError::Usage(_))));
+        assert!(matches!(Command::parse("pm list packages -d"), Err(ParseError::Usage(_))));
+    }
+
+    #[test]
+    fn other_text_is_not_a_command() {
+        assert_eq!(Command::parse("ro.build.date"), Err(ParseError::NotACommand));
+        assert_eq!(Command::parse("运行时间"), Err(ParseError::NotACommand));
+        assert_eq!(Command::parse(""), Err(ParseError::NotACommand));
+    }
+}
@@ -0,0 +1,98 @@
+/*
+ * Access to the Android application `Context` from native code.
+ */
+
+use jni::errors::Result;
+use jni::objects::{JObject, JString};
+use jni::JNIEnv;
+
+use crate::jni_cache;
+
+/// An `android.provider.Settings` table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Namespace {
+    System,
+    Secure,
+    Global,
+}
+
+impl Namespace {
+    /// The `android.provider.Settings` nested class holding the namespace.
+    fn class(self) -> &'static str {
+        match self {
+            Namespace::System => "android/provider/Settings$System",
+            Namespace::Secure => "android/provider/Settings$Secure",
+            Namespace::Global => "android/provider/Settings$Global",
+        }
+    }
+}
+
+/// Returns the current `Application` via `ActivityThread.currentApplication()`,
+/// for entry points such as `modifyParams` that are not handed a `Context`.
+pub fn application_context<'a>(env: &JNIEnv<'a>) -> Result<JObject<'a>> {
+    let cache = jni_cache::get(env)?;
+    cache.current_application.call_object(env, &cache.activity_thread, &[])
+}
+
+/// `context.getSystemService(name)`, e.g. `"window"` or `"location"`.
+pub fn system_service<'a>(env: &JNIEnv<'a>, context: JObject<'a>, name: &str) -> Result<JObject<'a>> {
+    let name = env.new_string(name)?;
+    jni_cache::get(env)?.get_system_service.call_object(env, context, &[name.into()])
+}
+
+/// `Build.VERSION.SDK_INT`.
+pub fn sdk_int(env: &JNIEnv) -> Result<i32> {
+    let cache = jni_cache::get(env)?;
+    cache.sdk_int.get_int(env, &cache.build_version)
+}
+
+/// `Settings.<namespace>.getString(context.getContentResolver(), name)`,
+/// `None` when the setting does not exist.
+pub fn settings_string<'a>(
+    env: &JNIEnv<'a>,
+    context: JObject<'a>,
+    namespace: Namespace,
+    name: &str,
+) -> Result<Option<String>> {
+    let resolver = env.call_method(context, "getContentResolver", "()Landroid/content/ContentResolver;", &[])?.l()?;
+    let name = env.new_string(name)?;
+    let value = env
+        .call_static_method(
+            namespace.class(),
+            "getString",
+            "(Landroid/content/ContentResolver;Ljava/lang/String;)Ljava/lang/String;",
+            &[resolver.into(), name.into()],
+        )?
+        .l()?;
+    optional_string(env, value)
+}
+
+/// Clears a pending Java exception of class `class` and returns true, so a
+/// caller can turn e.g. a `SecurityException` into a result state. Other
+/// exceptions are left pending.
+pub fn catch_exception(env: &JNIEnv, class: &str) -> Result<bool> {
+    if !env.exception_check()? {
+        return Ok(false);
+    }
+    let throwable = env.exception_occurred()?;
+    env.exception_clear()?;
+    if env.is_instance_of(throwable, class)? {
+        return Ok(true);
+    }
+    env.throw(throwable)?;
+    Ok(false)
+}
+
+/// A Java string, `None` when null. An empty string is `Some("")`.
+pub fn optional_string(env: &JNIEnv, value: JObject) -> Result<Option<String>> {
+    if value.is_null() {
+        return Ok(None);
+    }
+    Ok(Some(env.get_string(JString::from(value))?.into()))
+}
+
+/// A Java string, `None` when null or empty, for getters such as
+/// `getNetworkOperatorName` that return `""` when there is nothing to report.
+pub fn non_empty_string(env: &JNIEnv, value: JObject) -> Result<Option<String>> {
+    Ok(optional_string(env, value)?.filter(|value| !value.is_empty()))
+}
@@ -0,0 +1,399 @@
+/*
+ * CPU and ABI information behind Build.CPU_ABI, Build.SUPPORTED_ABIS and
+ * ro.product.cpu.abilist*.
+ */
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+
+use crate::invalid_data;
+use crate::props;
+
+pub const PROC_CPUINFO_PATH: &str = "/proc/cpuinfo";
+pub const SYSFS_CPU_PATH: &str = "/sys/devices/system/cpu";
+
+/// One `processor : N` block of `/proc/cpuinfo`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Processor {
+    pub id: u32,
+    #[serde(flatten)]
+    pub fields: BTreeMap<String, String>,
+}
+
+impl Processor {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields.get(key).map(String::as_str)
+    }
+
+    /// `Features` on ARM, `flags` on x86.
+    pub fn features(&self) -> Vec<String> {
+        self.get("Features")
+            .or_else(|| self.get("flags"))
+            .map(|features| features.split_whitespace().map(String::from).collect())
+            .unwrap_or_default()
+    }
+
+    /// `model name` on x86 and newer ARM kernels, otherwise the core name
+    /// derived from `CPU implementer` and `CPU part`.
+    pub fn model_name(&self) -> Option<String> {
+        if let Some(name) = self.get("model name") {
+            return Some(name.to_string());
+        }
+        let implementer = parse_hex(self.get("CPU implementer")?)?;
+        let part = parse_hex(self.get("CPU part")?)?;
+        arm_core_name(implementer, part).map(String::from)
+    }
+}
+
+/// `/proc/cpuinfo` as printed by the ARM and x86 kernels: per-processor
+/// blocks plus machine-wide lines such as `Hardware`.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize)]
+pub struct ProcCpuInfo {
+    pub processors: Vec<Processor>,
+    pub global: BTreeMap<String, String>,
+}
+
+impl ProcCpuInfo {
+    pub fn read() -> io::Result<ProcCpuInfo> {
+        Ok(ProcCpuInfo::parse(&std::fs::read_to_string(PROC_CPUINFO_PATH)?))
+    }
+
+    pub fn parse(text: &str) -> ProcCpuInfo {
+        let mut info = ProcCpuInfo::default();
+        let mut in_processor = false;
+        for line in text.lines() {
+            if line.trim().is_empty() {
+                in_processor = false;
+                continue;
+            }
+            let (key, value) = match line.split_once(':') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => continue,
+            };
+            if key == "processor" {
+                if let Ok(id) = value.parse() {
+                    info.processors.push(Processor { id, fields: BTreeMap::new() });
+                    in_processor = true;
+                    continue;
+                }
+            }
+            match info.processors.last_mut() {
+                Some(processor) if in_processor => processor.fields.insert(key.to_string(), value.to_string()),
+                _ => info.global.insert(key.to_string(), value.to_string()),
+            };
+        }
+        info
+    }
+
+    /// The SoC name some vendor kernels print, e.g. `Qualcomm Technologies, Inc SM8250`.
+    pub fn hardware(&self) -> Option<&str> {
+        self.global.get("Hardware").map(String::as_str)
+    }
+
+    /// The legacy ARM `Processor` line, or the first processor's model name.
+    pub fn model_name(&self) -> Option<String> {
+        self.global
+            .get("Processor")
+            .cloned()
+            .or_else(|| self.processors.first().and_then(Processor::model_name))
+    }
+
+    pub fn processor(&self, id: u32) -> Option<&Processor> {
+        self.processors.iter().find(|processor| processor.id == id)
+    }
+}
+
+/// CPUs that share a maximum frequency, i.e. one big.LITTLE cluster.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Cluster {
+    pub cpus: Vec<u32>,
+    pub max_freq_khz: Option<u64>,
+    pub core: Option<String>,
+}
+
+/// What `/sys/devices/system/cpu` says about the CPUs.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize)]
+pub struct Topology {
+    pub possible: Vec<u32>,
+    pub online: Vec<u32>,
+    pub max_freq_khz: BTreeMap<u32, u64>,
+}
+
+impl Topology {
+    /// Reads `possible`, `online` and each `cpuN/cpufreq/cpuinfo_max_freq` under `root`.
+    pub fn read(root: &Path) -> io::Result<Topology> {
+        let possible = parse_cpu_list(&std::fs::read_to_string(root.join("possible"))?)
+            .ok_or_else(|| invalid_data("malformed cpu/possible".to_string()))?;
+        let online = std::fs::read_to_string(root.join("online"))
+            .ok()
+            .and_then(|text| parse_cpu_list(&text))
+            .unwrap_or_default();
+        let max_freq_khz = possible
+            .iter()
+            .filter_map(|&cpu| {
+                let path = root.join(format!("cpu{}/cpufreq/cpuinfo_max_freq", cpu));
+                let freq = std::fs::read_to_string(path).ok()?.trim().parse().ok()?;
+                Some((cpu, freq))
+            })
+            .collect();
+        Ok(Topology { possible, online, max_freq_khz })
+    }
+
+    /// Groups the possible CPUs by maximum frequency, in CPU order. CPUs
+    /// without cpufreq end up together in a cluster with no frequency.
+    pub fn clusters(&self) -> Vec<Cluster> {
+        let mut clusters: Vec<Cluster> = Vec::new();
+        for &cpu in &self.possible {
+            let max_freq_khz = self.max_freq_khz.get(&cpu).copied();
+            match clusters.iter_mut().find(|cluster| cluster.max_freq_khz == max_freq_khz) {
+                Some(cluster) => cluster.cpus.push(cpu),
+                None => clusters.push(Cluster { cpus: vec![cpu], max_freq_khz, core: None }),
+            }
+        }
+        clusters
+    }
+}
+
+/// The ABI lists, as `Build.CPU_ABI`, `CPU_ABI2` and `SUPPORTED_*_ABIS` report them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AbiInfo {
+    pub cpu_abi: String,
+    pub cpu_abi2: Option<String>,
+    pub supported_abis: Vec<String>,
+    pub supported_32_bit_abis: Vec<String>,
+    pub supported_64_bit_abis: Vec<String>,
+}
+
+impl AbiInfo {
+    /// Reads `ro.product.cpu.abi*`. Off Android, or on devices predating the
+    /// lists, falls back to the ABI this library was built for.
+    pub fn read() -> AbiInfo {
+        let mut supported_abis = props::get_list("ro.product.cpu.abilist");
+        if supported_abis.is_empty() {
+            supported_abis = [props::get("ro.product.cpu.abi"), props::get("ro.product.cpu.abi2")]
+                .into_iter()
+                .flatten()
+                .collect();
+        }
+        if supported_abis.is_empty() {
+            supported_abis.push(build_abi().to_string());
+        }
+        let cpu_abi = props::get("ro.product.cpu.abi").unwrap_or_else(|| supported_abis[0].clone());
+        let cpu_abi2 = supported_abis.iter().find(|abi| **abi != cpu_abi).cloned();
+        let mut supported_32_bit_abis = props::get_list("ro.product.cpu.abilist32");
+        let mut supported_64_bit_abis = props::get_list("ro.product.cpu.abilist64");
+        if supported_32_bit_abis.is_empty() && supported_64_bit_abis.is_empty() {
+            let (abis_64, abis_32) = supported_abis.iter().cloned().partition(|abi| is_64_bit_abi(abi));
+            supported_64_bit_abis = abis_64;
+            supported_32_bit_abis = abis_32;
+        }
+        AbiInfo { cpu_abi, cpu_abi2, supported_abis, supported_32_bit_abis, supported_64_bit_abis }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CpuInfo {
+    pub hardware: Option<String>,
+    pub model_name: Option<String>,
+    pub core_count: usize,
+    pub online: Vec<u32>,
+    pub clusters: Vec<Cluster>,
+    pub features: Vec<String>,
+    pub processors: Vec<Processor>,
+    pub abi: AbiInfo,
+}
+
+impl CpuInfo {
+    pub fn read() -> io::Result<CpuInfo> {
+        let topology = Topology::read(Path::new(SYSFS_CPU_PATH))
+            .map_err(|e| warn!("Failed to read {}: {}", SYSFS_CPU_PATH, e))
+            .unwrap_or_default();
+        Ok(CpuInfo::new(ProcCpuInfo::read()?, topology, AbiInfo::read()))
+    }
+
+    pub fn new(cpuinfo: ProcCpuInfo, topology: Topology, abi: AbiInfo) -> CpuInfo {
+        let core_count = if topology.possible.is_empty() {
+            cpuinfo.processors.len()
+        } else {
+            topology.possible.len()
+        };
+        let mut clusters = topology.clusters();
+        for cluster in &mut clusters {
+            cluster.core = cluster.cpus.first().and_then(|&cpu| cpuinfo.processor(cpu)).and_then(Processor::model_name);
+        }
+        CpuInfo {
+            hardware: cpuinfo.hardware().map(String::from),
+            model_name: cpuinfo.model_name(),
+            core_count,
+            online: topology.online,
+            clusters,
+            features: cpuinfo.processors.first().map(Processor::features).unwrap_or_default(),
+            processors: cpuinfo.processors,
+            abi,
+        }
+    }
+}
+
+/// Parses the kernel's CPU list format, e.g. `0-3,6,8-9`.
+pub fn parse_cpu_list(text: &str) -> Option<Vec<u32>> {
+    let mut cpus = Vec::new();
+    for range in text.trim().split(',').filter(|range| !range.is_empty()) {
+        match range.split_once('-') {
+            Some((first, last)) => cpus.extend(first.parse::<u32>().ok()?..=last.parse().ok()?),
+            None => cpus.push(range.parse().ok()?),
+        }
+    }
+    Some(cpus)
+}
+
+/// The Android ABI name of the target this library was compiled for.
+pub fn build_abi() -> &'static str {
+    match std::env::consts::ARCH {
+        "aarch64" => "arm64-v8a",
+        "arm" => "armeabi-v7a",
+        "x86" => "x86",
+        "x86_64" => "x86_64",
+        arch => arch,
+    }
+}
+
+fn is_64_bit_abi(abi: &str) -> bool {
+    matches!(abi, "arm64-v8a" | "x86_64" | "mips64" | "riscv64")
+}
+
+fn parse_hex(value: &str) -> Option<u32> {
+    u32::from_str_radix(value.trim_start_matches("0x"), 16).ok()
+}
+
+/// Names for the Arm Ltd. (implementer `0x41`) cores found in Android devices.
+fn arm_core_name(implementer: u32, part: u32) -> Option<&'static str> {
+    if implementer != 0x41 {
+        return None;
+    }
+    Some(match part {
+        0xc05 => "Cortex-A5",
+        0xc07 => "Cortex-A7",
+        0xc09 => "Cortex-A9",
+        0xc0d | 0xc0e => "Cortex-A17",
+        0xc0f => "Cortex-A15",
+        0xd03 => "Cortex-A53",
+        0xd04 => "Cortex-A35",
+        0xd05 => "Cortex-A55",
+        0xd07 => "Cortex-A57",
+        0xd08 => "Cortex-A72",
+        0xd09 => "Cortex-A73",
+        0xd0a => "Cortex-A75",
+        0xd0b => "Cortex-A76",
+        0xd0d => "Cortex-A77",
+        0xd41 => "Cortex-A78",
+        0xd44 => "Cortex-X1",
+        0xd46 => "Cortex-A510",
+        0xd47 => "Cortex-A710",
+        0xd48 => "Cortex-X2",
+        0xd4d => "Cortex-A715",
+        0xd4e => "Cortex-X3",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn abi() -> AbiInfo {
+        AbiInfo {
+            cpu_abi: "arm64-v8a".to_string(),
+            cpu_abi2: Some("armeabi-v7a".to_string()),
+            supported_abis: vec!["arm64-v8a".to_string(), "armeabi-v7a".to_string(), "armeabi".to_string()],
+            supported_32_bit_abis: vec!["armeabi-v7a".to_string(), "armeabi".to_string()],
+            supported_64_bit_abis: vec!["arm64-v8a".to_string()],
+        }
+    }
+
+    #[test]
+    fn parses_arm64_cpuinfo() {
+        let info = ProcCpuInfo::parse(include_str!("../tests/fixtures/cpuinfo_arm64.txt"));
+        assert_eq!(info.processors.len(), 8);
+        assert_eq!(info.hardware(), Some("Qualcomm Technologies, Inc SM8250"));
+        assert_eq!(info.model_name().as_deref(), Some("Cortex-A55"));
+        assert_eq!(info.processor(7).unwrap().model_name().as_deref(), Some("Cortex-X1"));
+        assert_eq!(info.processor(4).unwrap().get("CPU variant"), Some("0x4"));
+        assert!(info.processors[0].features().contains(&"asimddp".to_string()));
+    }
+
+    #[test]
+    fn parses_armv7_cpuinfo_with_legacy_header() {
+        let info = ProcCpuInfo::parse(include_str!("../tests/fixtures/cpuinfo_armv7.txt"));
+        assert_eq!(info.processors.len(), 4);
+        assert_eq!(info.model_name().as_deref(), Some("ARMv7 Processor rev 1 (v7l)"));
+        assert_eq!(info.processors[0].model_name().as_deref(), Some("Cortex-A7"));
+        assert_eq!(info.hardware(), Some("MT6582"));
+        assert_eq!(info.global.get("Serial").map(String::as_str), Some("0000000000000000"));
+        assert!(!info.processors[3].fields.contains_key("Hardware"));
+    }
+
+    #[test]
+    fn parses_x86_cpuinfo() {
+        let info = ProcCpuInfo::parse(include_str!("../tests/fixtures/cpuinfo_x86_64.txt"));
+        assert_eq!(info.processors.len(), 2);
+        assert_eq!(info.hardware(), None);
+        assert_eq!(info.model_name().as_deref(), Some("Intel(R) Core(TM) i7-8700K CPU @ 3.70GHz"));
+        assert_eq!(info.processors[1].get("core id"), Some("1"));
+        assert_eq!(info.processors[1].get("power management"), Some(""));
+        assert!(info.processors[0].features().contains(&"sse4_2".to_string()));
+    }
+
+    #[test]
+    fn parses_cpu_lists() {
+        assert_eq!(parse_cpu_list("0-7\n"), Some(vec![0, 1, 2, 3, 4, 5, 6, 7]));
+        assert_eq!(parse_cpu_list("0,2-3,6"), Some(vec![0, 2, 3, 6]));
+        assert_eq!(parse_cpu_list(""), Some(vec![]));
+        assert_eq!(parse_cpu_list("0-x"), None);
+    }
+
+    #[test]
+    fn groups_big_little_clusters_from_sysfs() {
+        let root = std::env::temp_dir().join(format!("modify_mac_cpu_{}", std::process::id()));
+        for (cpu, freq) in [(0, 1804800), (1, 1804800), (2, 1804800), (3, 1804800), (4, 2419200), (5, 2419200), (6, 2419200), (7, 2841600)] {
+            let dir = root.join(format!("cpu{}/cpufreq", cpu));
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("cpuinfo_max_freq"), format!("{}\n", freq)).unwrap();
+        }
+        fs::write(root.join("possible"), "0-7\n").unwrap();
+        fs::write(root.join("online"), "0-5\n").unwrap();
+        let topology = Topology::read(&root);
+        fs::remove_dir_all(&root).unwrap();
+
+        let cpuinfo = ProcCpuInfo::parse(include_str!("../tests/fixtures/cpuinfo_arm64.txt"));
+        let info = CpuInfo::new(cpuinfo, topology.unwrap(), abi());
+        assert_eq!(info.core_count, 8);
+        assert_eq!(info.online, [0, 1, 2, 3, 4, 5]);
+        assert_eq!(
+            info.clusters,
+            [
+                Cluster { cpus: vec![0, 1, 2, 3], max_freq_khz: Some(1804800), core: Some("Cortex-A55".to_string()) },
+                Cluster { cpus: vec![4, 5, 6], max_freq_khz: Some(2419200), core: Some("Cortex-A77".to_string()) },
+                Cluster { cpus: vec![7], max_freq_khz: Some(2841600), core: Some("Cortex-X1".to_string()) },
+            ]
+        );
+    }
+
+    #[test]
+    fn counts_processors_without_sysfs() {
+        let cpuinfo = ProcCpuInfo::parse(include_str!("../tests/fixtures/cpuinfo_x86_64.txt"));
+        let info = CpuInfo::new(cpuinfo, Topology::default(), abi());
+        assert_eq!(info.core_count, 2);
+        assert!(info.clusters.is_empty());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn host_abi_falls_back_to_build_target() {
+        let abi = AbiInfo::read();
+        assert_eq!(abi.cpu_abi, build_abi());
+        assert_eq!(abi.supported_abis, [build_abi()]);
+    }
+}
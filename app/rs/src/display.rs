@@ -0,0 +1,282 @@
+/*
+ * Display metrics model behind 主屏幕尺寸 and 主屏幕分辨率.
+ */
+
+use jni::errors::Result;
+use jni::objects::JObject;
+use jni::JNIEnv;
+use serde::Serialize;
+
+use crate::context;
+
+/// `DisplayMetrics.DENSITY_DEFAULT`: one dp is one px at this density.
+pub const DENSITY_DEFAULT: i32 = 160;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct DisplayInfo {
+    pub width_px: i32,
+    pub height_px: i32,
+    pub density_dpi: i32,
+    pub xdpi: f32,
+    pub ydpi: f32,
+    pub refresh_rate: f32,
+}
+
+/// Android `screenLayout` size classes, see `Configuration.SCREENLAYOUT_SIZE_*`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScreenClass {
+    Small,
+    Normal,
+    Large,
+    XLarge,
+}
+
+impl DisplayInfo {
+    /// Reads the default display of the running application.
+    pub fn current(env: &JNIEnv) -> Result<DisplayInfo> {
+        DisplayInfo::from_context(env, context::application_context(env)?)
+    }
+
+    /// Reads the real (non-decor-adjusted) metrics of the default display.
+    pub fn from_context<'a>(env: &JNIEnv<'a>, context: JObject<'a>) -> Result<DisplayInfo> {
+        let window_manager = context::system_service(env, context, "window")?;
+        let display = env
+            .call_method(window_manager, "getDefaultDisplay", "()Landroid/view/Display;", &[])?
+            .l()?;
+        let metrics = env.new_object("android/util/DisplayMetrics", "()V", &[])?;
+        env.call_method(display, "getRealMetrics", "(Landroid/util/DisplayMetrics;)V", &[metrics.into()])?;
+
+        Ok(DisplayInfo {
+            width_px: env.get_field(metrics, "widthPixels", "I")?.i()?,
+            height_px: env.get_field(metrics, "heightPixels", "I")?.i()?,
+            density_dpi: env.get_field(metrics, "densityDpi", "I")?.i()?,
+            xdpi: env.get_field(metrics, "xdpi", "F")?.f()?,
+            ydpi: env.get_field(metrics, "ydpi", "F")?.f()?,
+            refresh_rate: env.call_method(display, "getRefreshRate", "()F", &[])?.f()?,
+        })
+    }
+
+    /// `DisplayMetrics.density`, the dp to px scale factor.
+    pub fn density(&self) -> f32 {
+        self.density_dpi as f32 / DENSITY_DEFAULT as f32
+    }
+
+    pub fn width_inches(&self) -> f32 {
+        self.width_px as f32 / self.xdpi
+    }
+
+    pub fn height_inches(&self) -> f32 {
+        self.height_px as f32 / self.ydpi
+    }
+
+    pub fn diagonal_inches(&self) -> f32 {
+        self.width_inches().hypot(self.height_inches())
+    }
+
+    pub fn width_dp(&self) -> f32 {
+        px_to_dp(self.width_px, self.density_dpi)
+    }
+
+    pub fn height_dp(&self) -> f32 {
+        px_to_dp(self.height_px, self.density_dpi)
+    }
+
+    /// The `sw<N>dp` resource qualifier value.
+    pub fn smallest_width_dp(&self) -> f32 {
+        self.width_dp().min(self.height_dp())
+    }
+
+    pub fn density_bucket(&self) -> &'static str {
+        density_bucket(self.density_dpi)
+    }
+
+    pub fn screen_class(&self) -> ScreenClass {
+        screen_class(self.width_dp(), self.height_dp())
+    }
+
+    /// "1080x2400", the form shown for 主屏幕分辨率.
+    pub fn resolution(&self) -> String {
+        format!("{}x{}", self.width_px, self.height_px)
+    }
+}
+
+pub fn px_to_dp(px: i32, density_dpi: i32) -> f32 {
+    px as f32 * DENSITY_DEFAULT as f32 / density_dpi as f32
+}
+
+pub fn dp_to_px(dp: f32, density_dpi: i32) -> i32 {
+    (dp * density_dpi as f32 / DENSITY_DEFAULT as f32).round() as i32
+}
+
+/// Pixel density of a panel given its resolution and physical diagonal.
+pub fn dpi_for_diagonal(width_px: i32, height_px: i32, diagonal_inches: f32) -> f32 {
+    (width_px as f32).hypot(height_px as f32) / diagonal_inches
+}
+
+/// Physical diagonal of a panel given its resolution and pixel density.
+pub fn diagonal_for_dpi(width_px: i32, height_px: i32, dpi: f32) -> f32 {
+    (width_px as f32).hypot(height_px as f32) / dpi
+}
+
+/// Resolution of a panel given its physical size and pixel density.
+pub fn resolution_for_size(width_inches: f32, height_inches: f32, dpi: f32) -> (i32, i32) {
+    ((width_inches * dpi).round() as i32, (height_inches * dpi).round() as i32)
+}
+
+/// Nearest generalized density bucket, as used for `-<density>` resource qualifiers.
+pub fn density_bucket(density_dpi: i32) -> &'static str {
+    const BUCKETS: [(i32, &str); 7] = [
+        (120, "ldpi"),
+        (160, "mdpi"),
+        (213, "tvdpi"),
+        (240, "hdpi"),
+        (320, "xhdpi"),
+        (480, "xxhdpi"),
+        (640, "xxxhdpi"),
+    ];
+    BUCKETS
+        .iter()
+        .min_by_key(|(dpi, _)| (dpi - density_dpi).abs())
+        .map(|(_, name)| *name)
+        .unwrap()
+}
+
+/// Classifies a screen by its dp dimensions using the thresholds from the
+/// "Supporting different screen sizes" guide (960x720, 640x480, 470x320).
+pub fn screen_class(width_dp: f32, height_dp: f32) -> ScreenClass {
+    let long = width_dp.max(height_dp);
+    let short = width_dp.min(height_dp);
+    if long >= 960.0 && short >= 720.0 {
+        ScreenClass::XLarge
+    } else if long >= 640.0 && short >= 480.0 {
+        ScreenClass::Large
+    } else if long >= 470.0 && short >= 320.0 {
+        ScreenClass::Normal
+    } else {
+        ScreenClass::Small
+    }
+}
+
+/// `DisplayInfo` together with the values derived from it, as returned by `queryParams`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DisplayReport {
+    #[serde(flatten)]
+    pub info: DisplayInfo,
+    pub resolution: String,
+    pub density: f32,
+    pub diagonal_inches: f32,
+    pub width_dp: f32,
+    pub height_dp: f32,
+    pub smallest_width_dp: f32,
+    pub density_bucket: &'static str,
+    pub screen_class: ScreenClass,
+}
+
+impl From<DisplayInfo> for DisplayReport {
+    fn from(info: DisplayInfo) -> Self {
+        DisplayReport {
+            resolution: info.resolution(),
+            density: info.density(),
+            diagonal_inches: info.diagonal_inches(),
+            width_dp: info.width_dp(),
+            height_dp: info.height_dp(),
+            smallest_width_dp: info.smallest_width_dp(),
+            density_bucket: info.density_bucket(),
+            screen_class: info.screen_class(),
+            info,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 0.05, "{} != {}", actual, expected);
+    }
+
+    /// A 6.7" 1080x2400 phone reporting 440dpi.
+    fn phone() -> DisplayInfo {
+        DisplayInfo { width_px: 1080, height_px: 2400, density_dpi: 440, xdpi: 393.0, ydpi: 393.0, refresh_rate: 120.0 }
+    }
+
+    #[test]
+    fn converts_between_px_and_dp() {
+        assert_close(px_to_dp(1080, 480), 360.0);
+        assert_close(px_to_dp(160, DENSITY_DEFAULT), 160.0);
+        assert_close(px_to_dp(1080, 440), 392.7);
+        assert_eq!(dp_to_px(360.0, 480), 1080);
+        // 1dp at 420dpi is 2.625px, rounded.
+        assert_eq!(dp_to_px(1.0, 420), 3);
+        assert_eq!(dp_to_px(px_to_dp(2400, 440), 440), 2400);
+    }
+
+    #[test]
+    fn converts_between_dpi_diagonal_and_resolution() {
+        assert_close(dpi_for_diagonal(1080, 2400, 6.7), 392.8);
+        assert_close(diagonal_for_dpi(1080, 2400, 392.8), 6.7);
+        // A 3:4 panel 5" across.
+        assert_close(dpi_for_diagonal(960, 1280, 5.0), 320.0);
+        assert_close(diagonal_for_dpi(960, 1280, 320.0), 5.0);
+        assert_eq!(resolution_for_size(3.0, 4.0, 320.0), (960, 1280));
+        assert_eq!(resolution_for_size(2.5, 5.555, 432.0), (1080, 2400));
+    }
+
+    #[test]
+    fn known_device_lands_in_xxhdpi_normal() {
+        let report = DisplayReport::from(phone());
+        assert_eq!(report.resolution, "1080x2400");
+        assert_close(report.density, 2.75);
+        assert_close(report.diagonal_inches, 6.7);
+        assert_close(report.smallest_width_dp, 392.7);
+        assert_eq!(report.density_bucket, "xxhdpi");
+        assert_eq!(report.screen_class, ScreenClass::Normal);
+    }
+
+    #[test]
+    fn density_bucket_boundaries() {
+        // Exact buckets, and the midpoints between them, which go to the lower bucket.
+        let cases = [
+            (100, "ldpi"),
+            (120, "ldpi"),
+            (140, "ldpi"),
+            (141, "mdpi"),
+            (160, "mdpi"),
+            (186, "mdpi"),
+            (187, "tvdpi"),
+            (213, "tvdpi"),
+            (226, "tvdpi"),
+            (227, "hdpi"),
+            (240, "hdpi"),
+            (280, "hdpi"),
+            (281, "xhdpi"),
+            (320, "xhdpi"),
+            (400, "xhdpi"),
+            (401, "xxhdpi"),
+            (480, "xxhdpi"),
+            (560, "xxhdpi"),
+            (561, "xxxhdpi"),
+            (640, "xxxhdpi"),
+            (800, "xxxhdpi"),
+        ];
+        for (dpi, bucket) in cases {
+            assert_eq!(density_bucket(dpi), bucket, "{}dpi", dpi);
+        }
+    }
+
+    #[test]
+    fn screen_class_boundaries() {
+        assert_eq!(screen_class(320.0, 470.0), ScreenClass::Normal);
+        assert_eq!(screen_class(470.0, 320.0), ScreenClass::Normal);
+        assert_eq!(screen_class(319.9, 470.0), ScreenClass::Small);
+        assert_eq!(screen_class(320.0, 469.9), ScreenClass::Small);
+        assert_eq!(screen_class(480.0, 640.0), ScreenClass::Large);
+        assert_eq!(screen_class(479.9, 640.0), ScreenClass::Normal);
+        assert_eq!(screen_class(480.0, 639.9), ScreenClass::Normal);
+        assert_eq!(screen_class(720.0, 960.0), ScreenClass::XLarge);
+        assert_eq!(screen_class(719.9, 960.0), ScreenClass::Large);
+        assert_eq!(screen_class(960.0, 719.9), ScreenClass::Large);
+    }
+}
@@ -0,0 +1,163 @@
+/*
+ * Local reference frames for loops over Java arrays and lists. Every local
+ * reference a native method creates lives until it returns, and ART aborts
+ * once the table holds 512 of them; a frame per element frees each
+ * element's references before the next one is read.
+ */
+
+use jni::errors::Result;
+use jni::objects::JObject;
+use jni::sys::jobjectArray;
+use jni::JNIEnv;
+
+/// Local references one loop iteration is expected to need. Frames grow
+/// past this if needed; it only sizes the initial allocation.
+pub const ELEMENT_CAPACITY: i32 = 16;
+
+/// Runs `f` inside a new local reference frame and frees every local
+/// reference it created. `f` must not return local references: they are
+/// invalid once the frame is popped.
+pub fn with_local_frame<T, F>(env: &JNIEnv, capacity: i32, f: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T>,
+{
+    env.push_local_frame(capacity)?;
+    let result = f();
+    // Popping is allowed with an exception pending, so `f`'s error survives.
+    env.pop_local_frame(JObject::null())?;
+    result
+}
+
+/// Calls `f` with each element of a possibly null object array, each in its
+/// own local frame, and collects the results.
+pub fn map_array<'a, T, F>(env: &JNIEnv<'a>, array: jobjectArray, mut f: F) -> Result<Vec<T>>
+where
+    F: FnMut(JObject<'a>) -> Result<T>,
+{
+    if array.is_null() {
+        return Ok(Vec::new());
+    }
+    let length = env.get_array_length(array)?;
+    let mut results = Vec::with_capacity(length as usize);
+    for i in 0..length {
+        results.push(with_local_frame(env, ELEMENT_CAPACITY, || f(env.get_object_array_element(array, i)?))?);
+    }
+    Ok(results)
+}
+
+/// `map_array` for a `java.util.List`.
+pub fn map_list<'a, T, F>(env: &JNIEnv<'a>, list: JObject<'a>, mut f: F) -> Result<Vec<T>>
+where
+    F: FnMut(JObject<'a>) -> Result<T>,
+{
+    if list.is_null() {
+        return Ok(Vec::new());
+    }
+    let list = env.get_list(list)?;
+    let size = list.size()?;
+    let mut results = Vec::with_capacity(size as usize);
+    for i in 0..size {
+        results.push(with_local_frame(env, ELEMENT_CAPACITY, || f(list.get(i)?.unwrap_or_else(JObject::null)))?);
+    }
+    Ok(results)
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use jni::{InitArgsBuilder, JNIVersion, JavaVM};
+    use std::sync::OnceLock;
+
+    const ELEMENTS: i32 = 10_000;
+
+    fn jvm() -> &'static JavaVM {
+        static JVM: OnceLock<JavaVM> = OnceLock::new();
+        JVM.get_or_init(|| {
+            let args = InitArgsBuilder::new().version(JNIVersion::V8).option("-Xcheck:jni").build().unwrap();
+            JavaVM::new(args).unwrap()
+        })
+    }
+
+    /// `ELEMENTS` fresh objects, plus a `WeakReference` to each so the test
+    /// can tell whether anything still holds them.
+    fn objects(env: &JNIEnv) -> (jobjectArray, jobjectArray) {
+        let objects = env.new_object_array(ELEMENTS, "java/lang/Object", JObject::null()).unwrap();
+        let weak = env.new_object_array(ELEMENTS, "java/lang/ref/WeakReference", JObject::null()).unwrap();
+        for i in 0..ELEMENTS {
+            with_local_frame(env, ELEMENT_CAPACITY, || {
+                let object = env.new_object("java/lang/Object", "()V", &[])?;
+                let reference =
+                    env.new_object("java/lang/ref/WeakReference", "(Ljava/lang/Object;)V", &[object.into()])?;
+                env.set_object_array_element(objects, i, object)?;
+                env.set_object_array_element(weak, i, reference)
+            })
+            .unwrap();
+        }
+        (objects, weak)
+    }
+
+    /// Drops the array's hold on the objects, collects, and counts the
+    /// objects still alive. Leaked local references keep them reachable.
+    fn surviving(env: &JNIEnv, objects: jobjectArray, weak: jobjectArray) -> usize {
+        for i in 0..ELEMENTS {
+            env.set_object_array_element(objects, i, JObject::null()).unwrap();
+        }
+        env.call_static_method("java/lang/System", "gc", "()V", &[]).unwrap();
+        map_array(env, weak, |reference| {
+            Ok(!env.call_method(reference, "get", "()Ljava/lang/Object;", &[])?.l()?.is_null())
+        })
+        .unwrap()
+        .into_iter()
+        .filter(|alive| *alive)
+        .count()
+    }
+
+    #[test]
+    fn map_array_frees_element_references() {
+        let env = jvm().attach_current_thread().unwrap();
+        with_local_frame(&env, ELEMENT_CAPACITY, || {
+            let (objects, weak) = objects(&env);
+            let hashes = map_array(&env, objects, |object| env.call_method(object, "hashCode", "()I", &[])?.i())?;
+            assert_eq!(hashes.len(), ELEMENTS as usize);
+            assert_eq!(surviving(&env, objects, weak), 0);
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn map_list_frees_element_references() {
+        let env = jvm().attach_current_thread().unwrap();
+        with_local_frame(&env, ELEMENT_CAPACITY, || {
+            let (objects, weak) = objects(&env);
+            let list = env.new_object("java/util/ArrayList", "(I)V", &[ELEMENTS.into()])?;
+            for i in 0..ELEMENTS {
+                with_local_frame(&env, ELEMENT_CAPACITY, || {
+                    let object = env.get_object_array_element(objects, i)?;
+                    env.call_method(list, "add", "(Ljava/lang/Object;)Z", &[object.into()])?;
+                    Ok(())
+                })?;
+            }
+            let count = map_list(&env, list, |object| Ok(!object.is_null()))?;
+            assert_eq!(count.len(), ELEMENTS as usize);
+            env.call_method(list, "clear", "()V", &[])?;
+            assert_eq!(surviving(&env, objects, weak), 0);
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn errors_still_pop_the_frame() {
+        let env = jvm().attach_current_thread().unwrap();
+        let result: Result<()> = with_local_frame(&env, ELEMENT_CAPACITY, || {
+            env.new_string("local")?;
+            let null = JObject::null();
+            env.call_static_method("java/lang/Integer", "parseInt", "(Ljava/lang/String;)I", &[null.into()])?;
+            Ok(())
+        });
+        assert!(matches!(result, Err(jni::errors::Error::JavaException)));
+        assert!(env.exception_check().unwrap());
+        env.exception_clear().unwrap();
+    }
+}
@@ -0,0 +1,150 @@
+/*
+ * Device identifiers behind settings get secure android_id, getprop
+ * ro.serialno, Build.SERIAL and 序列号.
+ */
+
+use jni::errors::{Error, Result};
+use jni::objects::JObject;
+use jni::JNIEnv;
+use serde::Serialize;
+
+use crate::context::{self, Namespace};
+use crate::props;
+
+/// What `Build.SERIAL` and `Build.getSerial()` return when the caller may
+/// not see the serial.
+pub const UNKNOWN: &str = "unknown";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdState {
+    Available,
+    /// The source is unset or empty.
+    Empty,
+    /// The source answered `"unknown"`: on API 29+ the serial is only visible
+    /// with `READ_PRIVILEGED_PHONE_STATE`.
+    Restricted,
+    /// The source threw `SecurityException`.
+    PermissionDenied,
+    /// The source does not exist on this API level.
+    Unsupported,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Identifier {
+    pub state: IdState,
+    pub value: Option<String>,
+}
+
+impl Identifier {
+    /// Classifies a raw value: `None` or `""` is `Empty`, `"unknown"` is `Restricted`.
+    pub fn from_value(value: Option<String>) -> Identifier {
+        match value {
+            None => Identifier::state(IdState::Empty),
+            Some(value) if value.is_empty() => Identifier::state(IdState::Empty),
+            Some(value) if value.eq_ignore_ascii_case(UNKNOWN) => Identifier::state(IdState::Restricted),
+            Some(value) => Identifier { state: IdState::Available, value: Some(value) },
+        }
+    }
+
+    pub fn state(state: IdState) -> Identifier {
+        Identifier { state, value: None }
+    }
+}
+
+/// Every source of the hardware serial.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SerialReport {
+    /// The deprecated `Build.SERIAL` field, `"unknown"` for apps targeting API 28+.
+    pub build_serial: Identifier,
+    /// `Build.getSerial()`, API 26+, which needs `READ_PHONE_STATE` and, on
+    /// API 29+, `READ_PRIVILEGED_PHONE_STATE`.
+    pub get_serial: Identifier,
+    /// `ro.serialno`, which SELinux hides from untrusted apps on API 29+.
+    pub ro_serialno: Identifier,
+    /// True on API 29+ when no source gave the serial away.
+    pub restricted: bool,
+    /// The first available value.
+    pub serial: Option<String>,
+}
+
+impl SerialReport {
+    pub fn current(env: &JNIEnv) -> Result<SerialReport> {
+        let sdk_int = context::sdk_int(env)?;
+        Ok(SerialReport::new(build_serial(env)?, get_serial(env, sdk_int)?, ro_serialno(), sdk_int))
+    }
+
+    pub fn new(build_serial: Identifier, get_serial: Identifier, ro_serialno: Identifier, sdk_int: i32) -> SerialReport {
+        let serial = [&get_serial, &build_serial, &ro_serialno].iter().find_map(|id| id.value.clone());
+        SerialReport { restricted: sdk_int >= 29 && serial.is_none(), serial, build_serial, get_serial, ro_serialno }
+    }
+}
+
+/// `Settings.Secure.ANDROID_ID`. Since API 26 it is scoped to the app's
+/// signing key, user and device.
+pub fn android_id(env: &JNIEnv) -> Result<Identifier> {
+    android_id_from_context(env, context::application_context(env)?)
+}
+
+pub fn android_id_from_context<'a>(env: &JNIEnv<'a>, context: JObject<'a>) -> Result<Identifier> {
+    Ok(Identifier::from_value(context::settings_string(env, context, Namespace::Secure, "android_id")?))
+}
+
+/// `Build.SERIAL`.
+pub fn build_serial(env: &JNIEnv) -> Result<Identifier> {
+    let value = env.get_static_field("android/os/Build", "SERIAL", "Ljava/lang/String;")?.l()?;
+    Ok(Identifier::from_value(context::optional_string(env, value)?))
+}
+
+/// `Build.getSerial()`, `Unsupported` below API 26.
+pub fn get_serial(env: &JNIEnv, sdk_int: i32) -> Result<Identifier> {
+    if sdk_int < 26 {
+        return Ok(Identifier::state(IdState::Unsupported));
+    }
+    match env.call_static_method("android/os/Build", "getSerial", "()Ljava/lang/String;", &[]) {
+        Ok(value) => Ok(Identifier::from_value(context::optional_string(env, value.l()?)?)),
+        Err(Error::JavaException) if context::catch_exception(env, "java/lang/SecurityException")? => {
+            Ok(Identifier::state(IdState::PermissionDenied))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// `getprop ro.serialno`.
+pub fn ro_serialno() -> Identifier {
+    Identifier::from_value(props::get("ro.serialno"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_raw_values() {
+        assert_eq!(Identifier::from_value(None).state, IdState::Empty);
+        assert_eq!(Identifier::from_value(Some(String::new())).state, IdState::Empty);
+        assert_eq!(Identifier::from_value(Some("unknown".to_string())), Identifier::state(IdState::Restricted));
+        assert_eq!(
+            Identifier::from_value(Some("9a1f3c7e".to_string())),
+            Identifier { state: IdState::Available, value: Some("9a1f3c7e".to_string()) }
+        );
+    }
+
+    #[test]
+    fn serial_is_restricted_only_on_api_29_without_a_value() {
+        let restricted = || Identifier::state(IdState::Restricted);
+        let denied = Identifier::state(IdState::PermissionDenied);
+
+        let report = SerialReport::new(restricted(), denied.clone(), Identifier::state(IdState::Empty), 29);
+        assert!(report.restricted);
+        assert_eq!(report.serial, None);
+
+        let report = SerialReport::new(restricted(), denied, Identifier::state(IdState::Empty), 28);
+        assert!(!report.restricted);
+
+        let report =
+            SerialReport::new(restricted(), Identifier::from_value(Some("R58M123".to_string())), restricted(), 30);
+        assert!(!report.restricted);
+        assert_eq!(report.serial.as_deref(), Some("R58M123"));
+    }
+}
@@ -0,0 +1,376 @@
+/*
+ * Classes, method IDs and field IDs looked up once, in JNI_OnLoad, and
+ * shared by every call afterwards.
+ */
+
+use jni::errors::{Error, Result};
+use jni::objects::{GlobalRef, JFieldID, JMethodID, JObject, JStaticFieldID, JStaticMethodID, JValue};
+use jni::signature::{JavaType, Primitive};
+use jni::sys::{jfieldID, jmethodID};
+use jni::JNIEnv;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+static CACHE: RwLock<Option<Arc<JniCache>>> = RwLock::new(None);
+
+/// A lookup that failed while building the cache, naming the descriptor.
+#[derive(Debug)]
+pub struct DescriptorError {
+    pub descriptor: String,
+    pub source: Error,
+}
+
+impl fmt::Display for DescriptorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "JNI lookup failed for {}: {}", self.descriptor, self.source)
+    }
+}
+
+impl std::error::Error for DescriptorError {}
+
+/// An instance method ID. Method and field IDs stay valid, and may be used
+/// from any thread, for as long as their class is loaded, which the
+/// `GlobalRef`s in `JniCache` guarantee.
+#[derive(Debug, Clone, Copy)]
+pub struct Method(jmethodID);
+
+#[derive(Debug, Clone, Copy)]
+pub struct StaticMethod(jmethodID);
+
+#[derive(Debug, Clone, Copy)]
+pub struct Field(jfieldID);
+
+#[derive(Debug, Clone, Copy)]
+pub struct StaticField(jfieldID);
+
+unsafe impl Send for Method {}
+unsafe impl Sync for Method {}
+unsafe impl Send for StaticMethod {}
+unsafe impl Sync for StaticMethod {}
+unsafe impl Send for Field {}
+unsafe impl Sync for Field {}
+unsafe impl Send for StaticField {}
+unsafe impl Sync for StaticField {}
+
+impl Method {
+    pub fn call_object<'a>(self, env: &JNIEnv<'a>, object: JObject<'a>, args: &[JValue]) -> Result<JObject<'a>> {
+        env.call_method_unchecked(object, JMethodID::from(self.0), JavaType::Object(String::new()), args)?.l()
+    }
+
+    pub fn call_int<'a>(self, env: &JNIEnv<'a>, object: JObject<'a>, args: &[JValue]) -> Result<i32> {
+        env.call_method_unchecked(object, JMethodID::from(self.0), JavaType::Primitive(Primitive::Int), args)?.i()
+    }
+
+    pub fn call_long<'a>(self, env: &JNIEnv<'a>, object: JObject<'a>, args: &[JValue]) -> Result<i64> {
+        env.call_method_unchecked(object, JMethodID::from(self.0), JavaType::Primitive(Primitive::Long), args)?.j()
+    }
+
+    pub fn call_bool<'a>(self, env: &JNIEnv<'a>, object: JObject<'a>, args: &[JValue]) -> Result<bool> {
+        env.call_method_unchecked(object, JMethodID::from(self.0), JavaType::Primitive(Primitive::Boolean), args)?.z()
+    }
+}
+
+impl StaticMethod {
+    pub fn call_object<'a>(self, env: &JNIEnv<'a>, class: &GlobalRef, args: &[JValue]) -> Result<JObject<'a>> {
+        env.call_static_method_unchecked(class, JStaticMethodID::from(self.0), JavaType::Object(String::new()), args)?
+            .l()
+    }
+}
+
+impl Field {
+    pub fn get_object<'a>(self, env: &JNIEnv<'a>, object: JObject<'a>) -> Result<JObject<'a>> {
+        env.get_field_unchecked(object, JFieldID::from(self.0), JavaType::Object(String::new()))?.l()
+    }
+
+    pub fn get_int<'a>(self, env: &JNIEnv<'a>, object: JObject<'a>) -> Result<i32> {
+        env.get_field_unchecked(object, JFieldID::from(self.0), JavaType::Primitive(Primitive::Int))?.i()
+    }
+
+    pub fn get_long<'a>(self, env: &JNIEnv<'a>, object: JObject<'a>) -> Result<i64> {
+        env.get_field_unchecked(object, JFieldID::from(self.0), JavaType::Primitive(Primitive::Long))?.j()
+    }
+}
+
+impl StaticField {
+    pub fn get_int(self, env: &JNIEnv, class: &GlobalRef) -> Result<i32> {
+        env.get_static_field_unchecked(class, JStaticFieldID::from(self.0), JavaType::Primitive(Primitive::Int))?.i()
+    }
+}
+
+pub struct JniCache {
+    pub activity_thread: GlobalRef,
+    pub build_version: GlobalRef,
+    pub context: GlobalRef,
+    pub package_manager: GlobalRef,
+    pub package_info: GlobalRef,
+    pub application_info: GlobalRef,
+    pub signature: GlobalRef,
+    /// API 28+.
+    pub signing_info: Option<GlobalRef>,
+
+    pub sdk_int: StaticField,
+    pub current_application: StaticMethod,
+
+    pub get_package_manager: Method,
+    pub get_package_name: Method,
+    pub get_system_service: Method,
+
+    pub get_package_info: Method,
+
+    pub package_name: Field,
+    pub version_name: Field,
+    pub version_code: Field,
+    /// API 28+.
+    pub get_long_version_code: Option<Method>,
+    pub first_install_time: Field,
+    pub last_update_time: Field,
+    pub application_info_field: Field,
+    pub requested_permissions: Field,
+    pub signatures: Field,
+    /// API 28+.
+    pub signing_info_field: Option<Field>,
+
+    pub target_sdk_version: Field,
+    pub flags: Field,
+
+    pub to_byte_array: Method,
+    /// API 28+.
+    pub get_apk_contents_signers: Option<Method>,
+}
+
+impl JniCache {
+    /// Looks everything up. Members that only exist on newer API levels are
+    /// looked up, and required, only when the device is new enough, so a
+    /// misspelled descriptor fails here instead of on first use.
+    pub fn lookup(env: &JNIEnv) -> std::result::Result<JniCache, DescriptorError> {
+        let activity_thread = class(env, "android/app/ActivityThread")?;
+        let build_version = class(env, "android/os/Build$VERSION")?;
+        let context = class(env, "android/content/Context")?;
+        let package_manager = class(env, "android/content/pm/PackageManager")?;
+        let package_info = class(env, "android/content/pm/PackageInfo")?;
+        let application_info = class(env, "android/content/pm/ApplicationInfo")?;
+        let signature = class(env, "android/content/pm/Signature")?;
+
+        let sdk_int = static_field(env, &build_version, "android/os/Build$VERSION", "SDK_INT", "I")?;
+        let api_28 = sdk_int
+            .get_int(env, &build_version)
+            .map_err(|source| DescriptorError { descriptor: "android/os/Build$VERSION.SDK_INT".to_string(), source })?
+            >= 28;
+        let signing_info = match api_28 {
+            true => Some(class(env, "android/content/pm/SigningInfo")?),
+            false => None,
+        };
+
+        Ok(JniCache {
+            sdk_int,
+            current_application: static_method(
+                env,
+                &activity_thread,
+                "android/app/ActivityThread",
+                "currentApplication",
+                "()Landroid/app/Application;",
+            )?,
+            get_package_manager: method(
+                env,
+                &context,
+                "android/content/Context",
+                "getPackageManager",
+                "()Landroid/content/pm/PackageManager;",
+            )?,
+            get_package_name: method(
+                env,
+                &context,
+                "android/content/Context",
+                "getPackageName",
+                "()Ljava/lang/String;",
+            )?,
+            get_system_service: method(
+                env,
+                &context,
+                "android/content/Context",
+                "getSystemService",
+                "(Ljava/lang/String;)Ljava/lang/Object;",
+            )?,
+            get_package_info: method(
+                env,
+                &package_manager,
+                "android/content/pm/PackageManager",
+                "getPackageInfo",
+                "(Ljava/lang/String;I)Landroid/content/pm/PackageInfo;",
+            )?,
+            package_name: field(
+                env,
+                &package_info,
+                "android/content/pm/PackageInfo",
+                "packageName",
+                "Ljava/lang/String;",
+            )?,
+            version_name: field(
+                env,
+                &package_info,
+                "android/content/pm/PackageInfo",
+                "versionName",
+                "Ljava/lang/String;",
+            )?,
+            version_code: field(env, &package_info, "android/content/pm/PackageInfo", "versionCode", "I")?,
+            get_long_version_code: match api_28 {
+                true => {
+                    Some(method(env, &package_info, "android/content/pm/PackageInfo", "getLongVersionCode", "()J")?)
+                }
+                false => None,
+            },
+            first_install_time: field(env, &package_info, "android/content/pm/PackageInfo", "firstInstallTime", "J")?,
+            last_update_time: field(env, &package_info, "android/content/pm/PackageInfo", "lastUpdateTime", "J")?,
+            application_info_field: field(
+                env,
+                &package_info,
+                "android/content/pm/PackageInfo",
+                "applicationInfo",
+                "Landroid/content/pm/ApplicationInfo;",
+            )?,
+            requested_permissions: field(
+                env,
+                &package_info,
+                "android/content/pm/PackageInfo",
+                "requestedPermissions",
+                "[Ljava/lang/String;",
+            )?,
+            signatures: field(
+                env,
+                &package_info,
+                "android/content/pm/PackageInfo",
+                "signatures",
+                "[Landroid/content/pm/Signature;",
+            )?,
+            signing_info_field: match api_28 {
+                true => Some(field(
+                    env,
+                    &package_info,
+                    "android/content/pm/PackageInfo",
+                    "signingInfo",
+                    "Landroid/content/pm/SigningInfo;",
+                )?),
+                false => None,
+            },
+            target_sdk_version: field(
+                env,
+                &application_info,
+                "android/content/pm/ApplicationInfo",
+                "targetSdkVersion",
+                "I",
+            )?,
+            flags: field(env, &application_info, "android/content/pm/ApplicationInfo", "flags", "I")?,
+            to_byte_array: method(env, &signature, "android/content/pm/Signature", "toByteArray", "()[B")?,
+            get_apk_contents_signers: match &signing_info {
+                Some(signing_info) => Some(method(
+                    env,
+                    signing_info,
+                    "android/content/pm/SigningInfo",
+                    "getApkContentsSigners",
+                    "()[Landroid/content/pm/Signature;",
+                )?),
+                None => None,
+            },
+            activity_thread,
+            build_version,
+            context,
+            package_manager,
+            package_info,
+            application_info,
+            signature,
+            signing_info,
+        })
+    }
+}
+
+/// Builds the cache unless it already exists. Called from `JNI_OnLoad`.
+pub fn init(env: &JNIEnv) -> std::result::Result<Arc<JniCache>, DescriptorError> {
+    if let Some(cache) = CACHE.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        return Ok(cache.clone());
+    }
+    let mut slot = CACHE.write().unwrap_or_else(|e| e.into_inner());
+    // Another thread may have finished the lookup while this one waited.
+    if let Some(cache) = slot.as_ref() {
+        return Ok(cache.clone());
+    }
+    let cache = Arc::new(JniCache::lookup(env)?);
+    *slot = Some(cache.clone());
+    Ok(cache)
+}
+
+/// Drops the cache. The `GlobalRef`s are released once the last caller
+/// still holding the cache lets go of it. Called from `JNI_OnUnload`.
+pub fn clear() {
+    CACHE.write().unwrap_or_else(|e| e.into_inner()).take();
+}
+
+/// The cache, building it on first use if `JNI_OnLoad` did not.
+pub fn get(env: &JNIEnv) -> Result<Arc<JniCache>> {
+    init(env).map_err(|e| {
+        error!("{}", e);
+        e.source
+    })
+}
+
+fn class(env: &JNIEnv, name: &str) -> std::result::Result<GlobalRef, DescriptorError> {
+    env.find_class(name)
+        .and_then(|class| env.new_global_ref(class))
+        .map_err(|source| descriptor_error(env, name.to_string(), source))
+}
+
+fn method(
+    env: &JNIEnv,
+    class: &GlobalRef,
+    class_name: &str,
+    name: &str,
+    sig: &str,
+) -> std::result::Result<Method, DescriptorError> {
+    env.get_method_id(class, name, sig)
+        .map(|id| Method(id.into_inner()))
+        .map_err(|source| descriptor_error(env, format!("{}.{}{}", class_name, name, sig), source))
+}
+
+fn static_method(
+    env: &JNIEnv,
+    class: &GlobalRef,
+    class_name: &str,
+    name: &str,
+    sig: &str,
+) -> std::result::Result<StaticMethod, DescriptorError> {
+    env.get_static_method_id(class, name, sig)
+        .map(|id| StaticMethod(id.into_inner()))
+        .map_err(|source| descriptor_error(env, format!("{}.{}{}", class_name, name, sig), source))
+}
+
+fn field(
+    env: &JNIEnv,
+    class: &GlobalRef,
+    class_name: &str,
+    name: &str,
+    sig: &str,
+) -> std::result::Result<Field, DescriptorError> {
+    env.get_field_id(class, name, sig)
+        .map(|id| Field(id.into_inner()))
+        .map_err(|source| descriptor_error(env, format!("{}.{}:{}", class_name, name, sig), source))
+}
+
+fn static_field(
+    env: &JNIEnv,
+    class: &GlobalRef,
+    class_name: &str,
+    name: &str,
+    sig: &str,
+) -> std::result::Result<StaticField, DescriptorError> {
+    env.get_static_field_id(class, name, sig)
+        .map(|id| StaticField(id.into_inner()))
+        .map_err(|source| descriptor_error(env, format!("{}.{}:{}", class_name, name, sig), source))
+}
+
+/// Clears the `NoSuchMethodError`/`ClassNotFoundException` a failed lookup
+/// leaves pending, so the error can be reported instead of thrown.
+fn descriptor_error(env: &JNIEnv, descriptor: String, source: Error) -> DescriptorError {
+    if env.exception_check().unwrap_or(false) {
+        let _ = env.exception_clear();
+    }
+    DescriptorError { descriptor, source }
+}
@@ -0,0 +1,225 @@
+/*
+ * Kernel version behind Kernel版本, uname -a and uname -r.
+ */
+
+use serde::Serialize;
+use std::ffi::CStr;
+use std::io;
+use std::mem::MaybeUninit;
+use std::os::raw::c_char;
+
+use crate::invalid_data;
+
+pub const PROC_VERSION_PATH: &str = "/proc/version";
+
+/// The `struct utsname` fields.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Uname {
+    pub sysname: String,
+    pub nodename: String,
+    pub release: String,
+    pub version: String,
+    pub machine: String,
+}
+
+impl Uname {
+    pub fn read() -> io::Result<Uname> {
+        let mut uts = MaybeUninit::<libc::utsname>::uninit();
+        if unsafe { libc::uname(uts.as_mut_ptr()) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let uts = unsafe { uts.assume_init() };
+        Ok(Uname {
+            sysname: field(&uts.sysname),
+            nodename: field(&uts.nodename),
+            release: field(&uts.release),
+            version: field(&uts.version),
+            machine: field(&uts.machine),
+        })
+    }
+
+    /// `uname -a`: sysname, nodename, release, version and machine.
+    pub fn all(&self) -> String {
+        format!("{} {} {} {} {}", self.sysname, self.nodename, self.release, self.version, self.machine)
+    }
+}
+
+/// `/proc/version` split into its parts, e.g.
+/// `Linux version <release> (<builder>) (<compiler>) #<build> <flags> <date>`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProcVersion {
+    pub release: String,
+    pub builder: Option<String>,
+    pub compiler: Option<String>,
+    /// Everything after the compiler, the same string as `uname -v`.
+    pub version: String,
+    pub build_number: Option<u32>,
+    /// `SMP`, `PREEMPT` and the like.
+    pub flags: Vec<String>,
+    /// The build timestamp as printed, `None` for reproducible builds (`@0`).
+    pub build_date: Option<String>,
+}
+
+impl ProcVersion {
+    pub fn read() -> io::Result<ProcVersion> {
+        let text = std::fs::read_to_string(PROC_VERSION_PATH)?;
+        ProcVersion::parse(&text)
+            .ok_or_else(|| invalid_data(format!("unrecognized {}: {:?}", PROC_VERSION_PATH, text)))
+    }
+
+    pub fn parse(text: &str) -> Option<ProcVersion> {
+        let rest = text.trim().strip_prefix("Linux version ")?;
+        let (release, mut rest) = rest.split_once(' ').unwrap_or((rest, ""));
+
+        let mut groups = Vec::new();
+        while let Some(stripped) = rest.trim_start().strip_prefix('(') {
+            let (group, after) = split_group(stripped)?;
+            groups.push(group.trim().to_string());
+            rest = after;
+        }
+        let mut groups = groups.into_iter();
+        let builder = groups.next();
+        let compiler = groups.next();
+
+        let version = rest.trim().to_string();
+        let mut tokens = version.split_whitespace().peekable();
+        // `#1`, or with a distro suffix as in `#111-Ubuntu`.
+        let build_tag = tokens.next_if(|token| token.starts_with('#'));
+        let build_number = build_tag.and_then(|tag| {
+            let digits = &tag[1..];
+            digits[..digits.find(|c: char| !c.is_ascii_digit()).unwrap_or(digits.len())].parse().ok()
+        });
+        let mut flags = Vec::new();
+        while let Some(token) = tokens.peek() {
+            if !is_flag(token) {
+                break;
+            }
+            flags.push(token.to_string());
+            tokens.next();
+        }
+        let build_date = tokens.collect::<Vec<_>>().join(" ");
+        let build_date = match build_date.as_str() {
+            "" | "@0" => None,
+            _ => Some(build_date),
+        };
+
+        Some(ProcVersion { release: release.to_string(), builder, compiler, version, build_number, flags, build_date })
+    }
+}
+
+/// Everything shown for Kernel版本.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct KernelInfo {
+    pub uname: Uname,
+    pub uname_all: String,
+    pub proc_version: Option<ProcVersion>,
+}
+
+impl KernelInfo {
+    pub fn read() -> io::Result<KernelInfo> {
+        let uname = Uname::read()?;
+        let proc_version = ProcVersion::read()
+            .map_err(|e| warn!("Failed to read {}: {}", PROC_VERSION_PATH, e))
+            .ok();
+        Ok(KernelInfo { uname_all: uname.all(), uname, proc_version })
+    }
+}
+
+/// Splits `"a (b) c) rest"` after an opening parenthesis into `("a (b) c", " rest")`.
+fn split_group(text: &str) -> Option<(&str, &str)> {
+    let mut depth = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return Some((&text[..i], &text[i + 1..])),
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+fn is_flag(token: &str) -> bool {
+    !token.is_empty() && token.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+fn field(chars: &[c_char]) -> String {
+    unsafe { CStr::from_ptr(chars.as_ptr()) }.to_string_lossy().into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_android_clang_kernel() {
+        let version = ProcVersion::parse(include_str!("../tests/fixtures/proc_version_android_clang.txt")).unwrap();
+        assert_eq!(version.release, "4.19.157-perf-g5c3e5b1a8b0c");
+        assert_eq!(version.builder.as_deref(), Some("builder@m1-xm-ota-bd203.bj.idc.xiaomi.com"));
+        let compiler = version.compiler.unwrap();
+        assert!(compiler.starts_with("Android (6443078 based on r383902) clang version 11.0.1"));
+        assert!(compiler.ends_with("b397f81060ce6d701042b782172ed13bee898b79)"));
+        assert_eq!(version.version, "#1 SMP PREEMPT Tue Jun 8 12:34:56 CST 2021");
+        assert_eq!(version.build_number, Some(1));
+        assert_eq!(version.flags, ["SMP", "PREEMPT"]);
+        assert_eq!(version.build_date.as_deref(), Some("Tue Jun 8 12:34:56 CST 2021"));
+    }
+
+    #[test]
+    fn parses_android_gcc_kernel() {
+        let version = ProcVersion::parse(include_str!("../tests/fixtures/proc_version_android_gcc.txt")).unwrap();
+        assert_eq!(version.release, "3.18.31-perf-g0d4b3c2");
+        assert_eq!(version.builder.as_deref(), Some("lineageos@build-3"));
+        assert_eq!(version.compiler.as_deref(), Some("gcc version 4.9.x 20150123 (prerelease) (GCC)"));
+        assert_eq!(version.build_date.as_deref(), Some("Wed Mar 14 03:04:55 UTC 2018"));
+    }
+
+    #[test]
+    fn parses_reproducible_build_without_date() {
+        let version =
+            ProcVersion::parse(include_str!("../tests/fixtures/proc_version_linux_reproducible.txt")).unwrap();
+        assert_eq!(version.release, "6.6.0-reproducible");
+        assert_eq!(version.builder.as_deref(), Some("builder@example.org"));
+        assert_eq!(version.compiler.as_deref(), Some("gcc (GCC) 13.2.0, GNU ld (GNU Binutils) 2.42"));
+        assert_eq!(version.version, "#1 SMP PREEMPT_DYNAMIC @0");
+        assert_eq!(version.build_number, Some(1));
+        assert_eq!(version.flags, ["SMP", "PREEMPT_DYNAMIC"]);
+        assert_eq!(version.build_date, None);
+    }
+
+    #[test]
+    fn parses_build_tag_with_distro_suffix() {
+        let version = ProcVersion::parse(include_str!("../tests/fixtures/proc_version_ubuntu.txt")).unwrap();
+        assert_eq!(version.release, "5.15.0-101-generic");
+        assert_eq!(version.builder.as_deref(), Some("buildd@lcy02-amd64-031"));
+        assert_eq!(version.build_number, Some(111));
+        assert_eq!(version.flags, ["SMP"]);
+        assert_eq!(version.build_date.as_deref(), Some("Tue Mar 5 20:16:58 UTC 2024"));
+
+        let version = ProcVersion::parse("Linux version 5.4.0 (b@h) (gcc) #101-Ubuntu SMP PREEMPT @0").unwrap();
+        assert_eq!((version.build_number, version.build_date), (Some(101), None));
+        assert_eq!(version.flags, ["SMP", "PREEMPT"]);
+        // A tag without digits is still consumed.
+        let version = ProcVersion::parse("Linux version 5.4.0 (b@h) (gcc) #custom SMP Mon Jan 1 2024").unwrap();
+        assert_eq!(version.build_number, None);
+        assert_eq!(version.flags, ["SMP"]);
+        assert_eq!(version.build_date.as_deref(), Some("Mon Jan 1 2024"));
+    }
+
+    #[test]
+    fn rejects_other_text() {
+        assert_eq!(ProcVersion::parse("Darwin Kernel Version 21.3.0"), None);
+        assert_eq!(ProcVersion::parse("Linux version 5.10 (unbalanced"), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn host_uname_matches_proc_version() {
+        let info = KernelInfo::read().unwrap();
+        assert_eq!(info.uname.sysname, "Linux");
+        let proc_version = info.proc_version.unwrap();
+        assert_eq!(proc_version.release, info.uname.release);
+        assert_eq!(proc_version.version, info.uname.version);
+        assert!(info.uname_all.starts_with("Linux "));
+    }
+}
@@ -1,11 +1,11 @@
-/**
+/*
  * @author Lime
  * @date 2022/2/15
  */
 
-use jni::sys::{jint,jobjectArray, jstring, JavaVM, JNI_VERSION_1_6};
-use jni::objects::{JClass,JObject,JValue,JString};
-use jni::{JNIEnv};
+use jni::sys::{jint, jobjectArray, jstring, JNI_ERR, JNI_VERSION_1_6};
+use jni::objects::{JClass,JObject,JString};
+use jni::{JNIEnv, JavaVM};
 use libc::c_void;
 
 //log
@@ -13,30 +13,66 @@ use libc::c_void;
 extern crate log;
 extern crate android_logger;
 
-use log::Level;
-use android_logger::Config;
+use serde::Serialize;
+use std::error::Error;
+use std::fmt::Debug;
+
+pub mod build_date;
+pub mod command;
+pub mod context;
+pub mod cpu;
+pub mod display;
+pub mod frame;
+pub mod identifiers;
+pub mod jni_cache;
+pub mod kernel;
+pub mod lifecycle;
+pub mod locale;
+pub mod location;
+pub mod package_info;
+pub mod packages;
+pub mod persistent_properties;
+pub mod prop_area;
+pub mod prop_rules;
+pub mod property_contexts;
+pub mod property_info;
+pub mod props;
+pub mod radio;
+pub mod registry;
+pub mod sensors;
+pub mod time;
+pub mod uptime;
+pub mod usage;
+pub mod worker;
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "system" fn JNI_OnLoad(vm: JavaVM, _reserved: *mut c_void) -> jint {
+    match lifecycle::on_load(vm) {
+        Ok(()) => JNI_VERSION_1_6,
+        Err(e) => {
+            error!("JNI_OnLoad: {}", e);
+            JNI_ERR
+        }
+    }
+}
 
 #[no_mangle]
 #[allow(non_snake_case)]
-unsafe fn JNI_OnLoad(jvm: JavaVM, _reserved: *mut c_void) -> jint {
-    android_logger::init_once(
-        Config::default()
-            .with_min_level(Level::Info)
-            .with_tag("ALOG"),
-    );
-    return JNI_VERSION_1_6;
+pub extern "system" fn JNI_OnUnload(_vm: JavaVM, _reserved: *mut c_void) {
+    lifecycle::on_unload();
 }
 
 
 #[no_mangle]
 pub extern "C" fn Java_com_windcloud_plugin_mac_utils_ModifyMacUtils_init(
-    env: JNIEnv,
+    _env: JNIEnv,
     _: JClass,
-    info: JObject
+    _info: jint
 ) -> jint {
     info!("Java_com_windcloud_plugin_mac_utils_ModifyMacUtils_init");
 
-    return 1;
+    1
 }
 
 
@@ -48,363 +84,676 @@ pub extern "C" fn Java_com_windcloud_plugin_mac_utils_ModifyMacUtils_getAppInfo(
 ) -> jint {
     info!("Java_com_windcloud_plugin_mac_utils_ModifyMacUtils_getAppInfo");
 
-    let package_manager = env.call_method(*context, "getPackageManager", "()Landroid/content/pm/PackageManager;", &[]).unwrap().l().unwrap();
-    
-    let package_name = env.call_method(*context, "getPackageName", "()Ljava/lang/String;", &[]).unwrap();
+    let signatures = package_info::PackageInfoView::own(&env, context, package_info::GET_SIGNATURES)
+        .and_then(|package_info| package_info.signatures());
 
-    let args = [package_name, JValue::Int(64)];
+    match signatures {
+        Ok(signatures) => match signatures.first() {
+            Some(signature) => signature.hash_code,
+            None => -1,
+        },
+        Err(e) => {
+            warn!("Failed to read the app signature: {}", e);
+            let _ = env.exception_clear();
+            -1
+        }
+    }
+}
 
-    let result = env.call_method(package_manager,
-         "getPackageInfo", "(Ljava/lang/String;I)Landroid/content/pm/PackageInfo;", &args).unwrap().l();
+#[no_mangle]
+pub extern "C" fn Java_com_windcloud_plugin_mac_utils_ModifyMacUtils_modifyParams(
+    env: JNIEnv,
+    _: JClass,
+    param: JString
+) -> jint {
 
-    let signatures = env.get_field(result.unwrap(), "signatures",  "[Landroid/content/pm/Signature;").unwrap().l().unwrap();
+    let param_str: String = env
+        .get_string(param)
+        .expect("Couldn't get Rust string from Java string")
+        .into();
 
-    let array = jobjectArray::from(*signatures);
+    info!("lime Java_com_windcloud_plugin_mac_utils_ModifyMacUtils_modifyParams: param = {}", param_str);
 
-    if env.get_array_length(array).unwrap() == 0 { return -1; }
+    match PARAMS.iter().find(|(key, _)| *key == param_str) {
+        Some((_, Handler::Plain(handler))) => handler(),
+        Some((_, Handler::Env(handler))) => handler(&env),
+        None => modify_command(&env, &param_str),
+    }
+}
 
-    let signature = env.get_object_array_element(array, 0).unwrap();
 
-    if signature.is_null() { return -1; }
+/// A `modifyParams` handler, with or without the `JNIEnv`.
+#[derive(Clone, Copy)]
+enum Handler {
+    Plain(fn() -> jint),
+    Env(fn(&JNIEnv) -> jint),
+}
 
-    let hash = env.call_method(signature, "hashCode", "()I", &[]).unwrap().i().unwrap();
+/// Every parameter `modifyParams` handles, in match order, with its handler.
+/// `registry::KEYS` is built from this table.
+const PARAMS: [(&str, Handler); 253] = [
+    ("运行时间", Handler::Plain(modify_runtime)),
+    ("品牌", Handler::Plain(modify_brand)),
+    ("型号", Handler::Plain(modify_model)),
+    ("硬件", Handler::Plain(modify_hardware)),
+    ("BOARD", Handler::Plain(modify_board)),
+    ("DISPLAY", Handler::Plain(modify_display)),
+    ("基带", Handler::Plain(modify_baseband)),
+    ("序列号", Handler::Env(modify_serial_number)),
+    ("主屏幕尺寸", Handler::Env(modify_main_screen_size)),
+    ("主屏幕分辨率", Handler::Env(modify_main_screen_resolution)),
+    ("GPS位置信息", Handler::Env(modify_gps_location_info)),
+    ("内存 已用/全部", Handler::Plain(modify_memory_usage)),
+    ("储存 已用/全部", Handler::Plain(modify_storage_usage)),
+    ("SDK INT", Handler::Plain(modify_sdk_int)),
+    ("RELEASE", Handler::Plain(modify_release)),
+    ("Version Code", Handler::Plain(modify_version_code)),
+    ("Bootloader：", Handler::Plain(modify_bootloader)),
+    ("ART", Handler::Plain(modify_art)),
+    ("Kernel版本", Handler::Plain(modify_kernel_version)),
+    ("ro.product.system.brand", Handler::Plain(modify_system_brand)),
+    ("ro.product.system.device", Handler::Plain(modify_system_device)),
+    ("ro.product.system.manufacturer", Handler::Plain(modify_system_manufacturer)),
+    ("ro.product.system.model", Handler::Plain(modify_system_model)),
+    ("ro.product.system.name", Handler::Plain(modify_system_name)),
+    ("settings get secure android_id", Handler::Env(modify_android_id)),
+    ("getprop ro.serialno", Handler::Plain(modify_serial_no)),
+    ("传感器信息", Handler::Env(modify_sensor_info)),
+    ("CPU信息", Handler::Plain(modify_cpu_info)),
+    ("Build.ID", Handler::Plain(modify_build_id)),
+    ("Build.DISPLAY", Handler::Plain(modify_build_display)),
+    ("Build.PRODUCT", Handler::Plain(modify_build_product)),
+    ("Build.DEVICE", Handler::Plain(modify_build_device)),
+    ("Build.BOARD", Handler::Plain(modify_build_board)),
+    ("Build.CPU_ABI", Handler::Plain(modify_build_cpu_abi)),
+    ("Build.CPU_ABI2", Handler::Plain(modify_build_cpu_abi2)),
+    ("Build.MANUFACTURER", Handler::Plain(modify_build_manufacturer)),
+    ("Build.BRAND", Handler::Plain(modify_build_brand)),
+    ("Build.MODEL", Handler::Plain(modify_build_model)),
+    ("Build.BOOTLOADER", Handler::Plain(modify_build_bootloader)),
+    ("Build.RADIO", Handler::Env(modify_build_radio)),
+    ("Build.HARDWARE", Handler::Plain(modify_build_hardware)),
+    ("Build.SERIAL", Handler::Env(modify_build_serial)),
+    ("Build.TYPE", Handler::Plain(modify_build_type)),
+    ("Build.TAGS", Handler::Plain(modify_build_tags)),
+    ("Build.FINGERPRINT", Handler::Plain(modify_build_fingerprint)),
+    ("Build.USER", Handler::Plain(modify_build_user)),
+    ("Build.HOST", Handler::Plain(modify_build_host)),
+    ("Build.TIME", Handler::Env(modify_build_time)),
+    ("Build.VERSION.INCREMENTAL", Handler::Plain(modify_build_version_incremental)),
+    ("Build.VERSION.RELEASE", Handler::Plain(modify_build_version_release)),
+    ("Build.VERSION.RELEASE_OR_CODENAME", Handler::Plain(modify_build_version_release_or_codename)),
+    ("Build.VERSION.BASE_OS", Handler::Plain(modify_build_version_base_os)),
+    ("Build.VERSION.SECURITY_PATCH", Handler::Plain(modify_build_version_security_patch)),
+    ("Build.VERSION.SDK", Handler::Plain(modify_build_version_sdk)),
+    ("Build.VERSION.CODENAME", Handler::Plain(modify_build_version_codename)),
+    ("Build.getRadioVersion()", Handler::Env(modify_build_radio_version)),
+    ("Build.VERSION.SDK_INT", Handler::Plain(modify_build_version_sdk_int)),
+    ("Build.VERSION.PREVIEW_SDK_INT", Handler::Plain(modify_build_version_preview_sdk_int)),
+    ("Build.SUPPORTED_ABIS", Handler::Plain(modify_build_supported_abis)),
+    ("Build.SUPPORTED_32_BIT_ABIS", Handler::Plain(modify_build_supported_32_bit_abis)),
+    ("Build.SUPPORTED_64_BIT_ABIS", Handler::Plain(modify_build_supported_64_bit_abis)),
+    ("Build.getFingerprintedPartitions()", Handler::Plain(modify_build_fingerprinted_partitions)),
+    ("App Install Time", Handler::Env(modify_app_install_time)),
+    ("App Last Update Time", Handler::Env(modify_app_last_update_time)),
+    ("ro.system.build.date", Handler::Env(modify_system_build_date)),
+    ("ro.system.build.date.utc", Handler::Env(modify_system_build_date_utc)),
+    ("ro.system.build.id", Handler::Plain(modify_system_build_id)),
+    ("ro.system.build.tags", Handler::Plain(modify_system_build_tags)),
+    ("ro.system.build.type", Handler::Plain(modify_system_build_type)),
+    ("ro.system.build.version.incremental", Handler::Plain(modify_system_build_version_incremental)),
+    ("ro.system.build.version.release", Handler::Plain(modify_system_build_version_release)),
+    ("ro.system.build.version.release_or_codename", Handler::Plain(modify_system_build_version_release_or_codename)),
+    ("ro.system.build.version.sdk", Handler::Plain(modify_system_build_version_sdk)),
+    ("ro.build.id", Handler::Plain(modify_ro_build_id)),
+    ("ro.build.keys", Handler::Plain(modify_ro_build_keys)),
+    ("ro.build.version.incremental", Handler::Plain(modify_ro_build_version_incremental)),
+    ("ro.build.version.sdk", Handler::Plain(modify_ro_build_version_sdk)),
+    ("ro.build.version.preview_sdk", Handler::Plain(modify_ro_build_version_preview_sdk)),
+    ("ro.build.version.preview_sdk_fingerprint", Handler::Plain(modify_ro_build_version_preview_sdk_fingerprint)),
+    ("ro.build.version.codename", Handler::Plain(modify_ro_build_version_codename)),
+    ("ro.build.version.all_codenames", Handler::Plain(modify_ro_build_version_all_codenames)),
+    ("ro.build.version.release", Handler::Plain(modify_ro_build_version_release)),
+    ("ro.build.version.release_or_codename", Handler::Plain(modify_ro_build_version_release_or_codename)),
+    ("ro.build.version.security_patch", Handler::Plain(modify_ro_build_version_security_patch)),
+    ("ro.build.version.base_os", Handler::Plain(modify_ro_build_version_base_os)),
+    ("ro.build.version.min_supported_target_sdk", Handler::Plain(modify_ro_build_version_min_supported_target_sdk)),
+    ("ro.build.date", Handler::Env(modify_ro_build_date)),
+    ("ro.build.date.utc", Handler::Env(modify_ro_build_date_utc)),
+    ("ro.build.type", Handler::Plain(modify_ro_build_type)),
+    ("ro.build.user", Handler::Plain(modify_ro_build_user)),
+    ("ro.build.host", Handler::Plain(modify_ro_build_host)),
+    ("ro.build.tags", Handler::Plain(modify_ro_build_tags)),
+    ("ro.build.flavor", Handler::Plain(modify_ro_build_flavor)),
+    ("ro.build.system_root_image", Handler::Plain(modify_ro_build_system_root_image)),
+    ("ro.product.cpu.abi", Handler::Plain(modify_ro_product_cpu_abi)),
+    ("ro.product.cpu.abilist", Handler::Plain(modify_ro_product_cpu_abilist)),
+    ("ro.product.cpu.abilist32", Handler::Plain(modify_ro_product_cpu_abilist32)),
+    ("ro.product.cpu.abilist64", Handler::Plain(modify_ro_product_cpu_abilist64)),
+    ("ro.product.locale", Handler::Env(modify_ro_product_locale)),
+    ("ro.wifi.channels", Handler::Plain(modify_ro_wifi_channels)),
+    ("ro.vendor.product.manufacturer.db", Handler::Plain(modify_ro_vendor_product_manufacturer_db)),
+    ("ro.vendor.product.device.db", Handler::Plain(modify_ro_vendor_product_device_db)),
+    ("rild.libpath", Handler::Plain(modify_rild_libpath)),
+    ("persist.rild.nitz_plmn", Handler::Plain(modify_persist_rild_nitz_plmn)),
+    ("persist.rild.nitz_long_ons_0", Handler::Plain(modify_persist_rild_nitz_long_ons_0)),
+    ("persist.rild.nitz_long_ons_1", Handler::Plain(modify_persist_rild_nitz_long_ons_1)),
+    ("persist.rild.nitz_long_ons_2", Handler::Plain(modify_persist_rild_nitz_long_ons_2)),
+    ("persist.rild.nitz_long_ons_3", Handler::Plain(modify_persist_rild_nitz_long_ons_3)),
+    ("persist.rild.nitz_short_ons_0", Handler::Plain(modify_persist_rild_nitz_short_ons_0)),
+    ("persist.rild.nitz_short_ons_1", Handler::Plain(modify_persist_rild_nitz_short_ons_1)),
+    ("persist.rild.nitz_short_ons_2", Handler::Plain(modify_persist_rild_nitz_short_ons_2)),
+    ("persist.rild.nitz_short_ons_3", Handler::Plain(modify_persist_rild_nitz_short_ons_3)),
+    ("ril.subscription.types", Handler::Plain(modify_ril_subscription_types)),
+    ("DEVICE_PROVISIONED", Handler::Plain(modify_device_provisioned)),
+    ("ro.telephony.default_network", Handler::Plain(modify_ro_telephony_default_network)),
+    ("persist.netmon.linger", Handler::Plain(modify_persist_netmon_linger)),
+    ("dalvik.vm.heapsize", Handler::Plain(modify_dalvik_vm_heapsize)),
+    ("dev.pm.dyn_samplingrate", Handler::Plain(modify_dev_pm_dyn_samplingrate)),
+    ("qcom.hw.aac.encoder", Handler::Plain(modify_qcom_hw_aac_encoder)),
+    ("persist.vendor.cne.feature", Handler::Plain(modify_persist_vendor_cne_feature)),
+    ("media.stagefright.enable-player", Handler::Plain(modify_media_stagefright_enable_player)),
+    ("media.stagefright.enable-http", Handler::Plain(modify_media_stagefright_enable_http)),
+    ("media.stagefright.enable-aac", Handler::Plain(modify_media_stagefright_enable_aac)),
+    ("media.stagefright.enable-qcp", Handler::Plain(modify_media_stagefright_enable_qcp)),
+    ("media.stagefright.enable-fma2dp", Handler::Plain(modify_media_stagefright_enable_fma2dp)),
+    ("media.stagefright.enable-scan", Handler::Plain(modify_media_stagefright_enable_scan)),
+    ("media.stagefright.thumbnail.prefer_hw_codecs", Handler::Plain(modify_media_stagefright_thumbnail_prefer_hw_codecs)),
+    ("mmp.enable.3g2", Handler::Plain(modify_mmp_enable_3g2)),
+    ("media.aac_51_output_enabled", Handler::Plain(modify_media_aac_51_output_enabled)),
+    ("media.settings.xml", Handler::Plain(modify_media_settings_xml)),
+    ("vendor.mm.enable.qcom_parser", Handler::Plain(modify_vendor_mm_enable_qcom_parser)),
+    ("persist.mm.enable.prefetch", Handler::Plain(modify_persist_mm_enable_prefetch)),
+    ("ro.netflix.bsp_rev", Handler::Plain(modify_ro_netflix_bsp_rev)),
+    ("ro.vendor.use_data_netmgrd", Handler::Plain(modify_ro_vendor_use_data_netmgrd)),
+    ("persist.vendor.data.mode", Handler::Plain(modify_persist_vendor_data_mode)),
+    ("persist.timed.enable", Handler::Plain(modify_persist_timed_enable)),
+    ("telephony.lteOnCdmaDevice", Handler::Plain(modify_telephony_lte_on_cdma_device)),
+    ("persist.fuse_sdcard", Handler::Plain(modify_persist_fuse_sdcard)),
+    ("ro.bluetooth.library_name", Handler::Plain(modify_ro_bluetooth_library_name)),
+    ("persist.vendor.btstack.aac_frm_ctl.enabled", Handler::Plain(modify_persist_vendor_btstack_aac_frm_ctl_enabled)),
+    ("persist.rmnet.data.enable", Handler::Plain(modify_persist_rmnet_data_enable)),
+    ("persist.data.wda.enable", Handler::Plain(modify_persist_data_wda_enable)),
+    ("persist.data.df.dl_mode", Handler::Plain(modify_persist_data_df_dl_mode)),
+    ("persist.data.df.ul_mode", Handler::Plain(modify_persist_data_df_ul_mode)),
+    ("persist.data.df.agg.dl_pkt", Handler::Plain(modify_persist_data_df_agg_dl_pkt)),
+    ("persist.data.df.agg.dl_size", Handler::Plain(modify_persist_data_df_agg_dl_size)),
+    ("persist.data.df.mux_count", Handler::Plain(modify_persist_data_df_mux_count)),
+    ("persist.data.df.iwlan_mux", Handler::Plain(modify_persist_data_df_iwlan_mux)),
+    ("persist.data.df.dev_name", Handler::Plain(modify_persist_data_df_dev_name)),
+    ("persist.debug.wfd.enable", Handler::Plain(modify_persist_debug_wfd_enable)),
+    ("persist.sys.wfd.virtual", Handler::Plain(modify_persist_sys_wfd_virtual)),
+    ("debug.sf.enable_hwc_vds", Handler::Plain(modify_debug_sf_enable_hwc_vds)),
+    ("debug.sf.latch_unsignaled", Handler::Plain(modify_debug_sf_latch_unsignaled)),
+    ("tunnel.audio.encode", Handler::Plain(modify_tunnel_audio_encode)),
+    ("use.voice.path.for.pcm.voip", Handler::Plain(modify_use_voice_path_for_pcm_voip)),
+    ("ro.nfc.port", Handler::Plain(modify_ro_nfc_port)),
+    ("sys.qca1530", Handler::Plain(modify_sys_qca1530)),
+    ("persist.debug.coresight.config", Handler::Plain(modify_persist_debug_coresight_config)),
+    ("ro.hwui.texture_cache_size", Handler::Plain(modify_ro_hwui_texture_cache_size)),
+    ("ro.hwui.layer_cache_size", Handler::Plain(modify_ro_hwui_layer_cache_size)),
+    ("ro.hwui.r_buffer_cache_size", Handler::Plain(modify_ro_hwui_r_buffer_cache_size)),
+    ("ro.hwui.path_cache_size", Handler::Plain(modify_ro_hwui_path_cache_size)),
+    ("ro.hwui.gradient_cache_size", Handler::Plain(modify_ro_hwui_gradient_cache_size)),
+    ("ro.hwui.drop_shadow_cache_size", Handler::Plain(modify_ro_hwui_drop_shadow_cache_size)),
+    ("ro.hwui.texture_cache_flushrate", Handler::Plain(modify_ro_hwui_texture_cache_flushrate)),
+    ("ro.hwui.text_small_cache_width", Handler::Plain(modify_ro_hwui_text_small_cache_width)),
+    ("ro.hwui.text_small_cache_height", Handler::Plain(modify_ro_hwui_text_small_cache_height)),
+    ("ro.hwui.text_large_cache_width", Handler::Plain(modify_ro_hwui_text_large_cache_width)),
+    ("ro.hwui.text_large_cache_height", Handler::Plain(modify_ro_hwui_text_large_cache_height)),
+    ("config.disable_rtt", Handler::Plain(modify_config_disable_rtt)),
+    ("persist.sys.force_sw_gles", Handler::Plain(modify_persist_sys_force_sw_gles)),
+    ("persist.vendor.radio.atfwd.start", Handler::Plain(modify_persist_vendor_radio_atfwd_start)),
+    ("ro.kernel.qemu.gles", Handler::Plain(modify_ro_kernel_qemu_gles)),
+    ("qemu.hw.mainkeys", Handler::Plain(modify_qemu_hw_mainkeys)),
+    ("vendor.camera.aux.packagelist", Handler::Plain(modify_vendor_camera_aux_packagelist)),
+    ("persist.vendor.camera.privapp.list", Handler::Plain(modify_persist_vendor_camera_privapp_list)),
+    ("persist.camera.privapp.list", Handler::Plain(modify_persist_camera_privapp_list)),
+    ("persist.vendor.overlay.izat.optin", Handler::Plain(modify_persist_vendor_overlay_izat_optin)),
+    ("persist.backup.ntpServer", Handler::Plain(modify_persist_backup_ntp_server)),
+    ("persist.vendor.sensors.enable.mag_filter", Handler::Plain(modify_persist_vendor_sensors_enable_mag_filter)),
+    ("ro.product.property_source_order", Handler::Plain(modify_ro_product_property_source_order)),
+    ("debug.stagefright.ccodec", Handler::Plain(modify_debug_stagefright_ccodec)),
+    ("ro.media.recorder-max-base-layer-fps", Handler::Plain(modify_ro_media_recorder_max_base_layer_fps)),
+    ("ro.charger.enable_suspend", Handler::Plain(modify_ro_charger_enable_suspend)),
+    ("persist.vendor.btstack.enable.twsplus", Handler::Plain(modify_persist_vendor_btstack_enable_twsplus)),
+    ("persist.vendor.bt.a2dp.hal.implementation", Handler::Plain(modify_persist_vendor_bt_a2dp_hal_implementation)),
+    ("persist.vendor.naruto.light.support", Handler::Plain(modify_persist_vendor_naruto_light_support)),
+    ("ro.apex.updatable", Handler::Plain(modify_ro_apex_updatable)),
+    ("persist.vendor.btstack.enable.lpa", Handler::Plain(modify_persist_vendor_btstack_enable_lpa)),
+    ("ro.audio.monitorRotation", Handler::Plain(modify_ro_audio_monitor_rotation)),
+    ("persist.camera.assert.panic", Handler::Plain(modify_persist_camera_assert_panic)),
+    ("ro.opcamera.support", Handler::Plain(modify_ro_opcamera_support)),
+    ("persist.vendor.ims.disableADBLogs", Handler::Plain(modify_persist_vendor_ims_disable_adb_logs)),
+    ("ro.gfx.driver.0", Handler::Plain(modify_ro_gfx_driver_0)),
+    ("ro.gfx.driver.1", Handler::Plain(modify_ro_gfx_driver_1)),
+    ("ro.treble.enabled", Handler::Plain(modify_ro_treble_enabled)),
+    ("net.bt.name", Handler::Plain(modify_net_bt_name)),
+    ("ro.vendor.qti.va_aosp.support", Handler::Plain(modify_ro_vendor_qti_va_aosp_support)),
+    ("ro.system.build.fingerprint", Handler::Plain(modify_ro_system_build_fingerprint)),
+    ("ro.product.build.fingerprint", Handler::Plain(modify_ro_product_build_fingerprint)),
+    ("ro.product.product.brand", Handler::Plain(modify_ro_product_product_brand)),
+    ("ro.product.product.device", Handler::Plain(modify_ro_product_product_device)),
+    ("ro.product.product.manufacturer", Handler::Plain(modify_ro_product_product_manufacturer)),
+    ("ro.product.product.name", Handler::Plain(modify_ro_product_product_name)),
+    ("ro.build.region", Handler::Plain(modify_ro_build_region)),
+    ("persist.sys.kernel", Handler::Plain(modify_persist_sys_kernel)),
+    ("persist.sys.main", Handler::Plain(modify_persist_sys_main)),
+    ("persist.sys.system", Handler::Plain(modify_persist_sys_system)),
+    ("persist.sys.radio", Handler::Plain(modify_persist_sys_radio)),
+    ("persist.sys.event", Handler::Plain(modify_persist_sys_event)),
+    ("persist.sys.perf", Handler::Plain(modify_persist_sys_perf)),
+    ("persist.sys.crash", Handler::Plain(modify_persist_sys_crash)),
+    ("persist.sys.qxdm", Handler::Plain(modify_persist_sys_qxdm)),
+    ("debug.sf.dump.primary", Handler::Plain(modify_debug_sf_dump_primary)),
+    ("debug.sf.dump.external", Handler::Plain(modify_debug_sf_dump_external)),
+    ("debug.sf.dump.enable", Handler::Plain(modify_debug_sf_dump_enable)),
+    ("debug.sf.dump", Handler::Plain(modify_debug_sf_dump)),
+    ("persist.sys.qsee", Handler::Plain(modify_persist_sys_qsee)),
+    ("persist.sys.tz", Handler::Env(modify_persist_sys_tz)),
+    ("persist.sys.bootloader", Handler::Plain(modify_persist_sys_bootloader)),
+    ("persist.sys.tcpdump.logsize", Handler::Plain(modify_persist_sys_tcpdump_logsize)),
+    ("persist.sys.tcpdump.lognum", Handler::Plain(modify_persist_sys_tcpdump_lognum)),
+    ("persist.log.tag.FuseDaemon", Handler::Plain(modify_persist_log_tag_fusedaemon)),
+    ("persist.sys.assert.panic", Handler::Plain(modify_persist_sys_assert_panic)),
+    ("persist.sys.assert.enable", Handler::Plain(modify_persist_sys_assert_enable)),
+    ("persist.sys.cfu_auto", Handler::Plain(modify_persist_sys_cfu_auto)),
+    ("ro.imei.check", Handler::Plain(modify_ro_imei_check)),
+    ("ro.vendor.custom.image", Handler::Plain(modify_ro_vendor_custom_image)),
+    ("ro.vendor.update.india", Handler::Plain(modify_ro_vendor_update_india)),
+    ("ro.build.os_type", Handler::Plain(modify_ro_build_os_type)),
+    ("persist.sys.oem.region", Handler::Plain(modify_persist_sys_oem_region)),
+    ("ro.build.real_device", Handler::Plain(modify_ro_build_real_device)),
+    ("ro.build.product", Handler::Plain(modify_ro_build_product)),
+    ("ro.product.device", Handler::Plain(modify_ro_product_device)),
+    ("ro.build.date.Ymd", Handler::Env(modify_ro_build_date_ymd)),
+    ("ro.build.date.ymd", Handler::Env(modify_ro_build_date_ymd_lowercase)),
+    ("ro.build.date.YmdHM", Handler::Env(modify_ro_build_date_ymdhm)),
+    ("ro.build.description", Handler::Plain(modify_ro_build_description)),
+    ("ro.common.soft", Handler::Plain(modify_ro_common_soft)),
+    ("ro.build.release_type", Handler::Plain(modify_ro_build_release_type)),
+    ("ro.build.soft.version", Handler::Plain(modify_ro_build_soft_version)),
+    ("ro.xxversion", Handler::Plain(modify_ro_xxversion)),
+    ("ro.build.kernel.id", Handler::Plain(modify_ro_build_kernel_id)),
+    ("ro.display.series", Handler::Plain(modify_ro_display_series)),
+    ("ro.build.ota.versionname", Handler::Plain(modify_ro_build_ota_versionname)),
+    ("ro.build.version.ota", Handler::Plain(modify_ro_build_version_ota)),
+    ("ro.build.soft.majorversion", Handler::Plain(modify_ro_build_soft_majorversion)),
+    ("ro.product.brand", Handler::Plain(modify_ro_product_brand)),
+    ("ro.product.manufacturer", Handler::Plain(modify_ro_product_manufacturer)),
+    ("persist.sys.timezone", Handler::Env(modify_persist_sys_timezone)),
+    ("ro.rom.version", Handler::Plain(modify_ro_rom_version)),
+    ("persist.vendor.ssr.enable_ramdumps", Handler::Plain(modify_persist_vendor_ssr_enable_ramdumps)),
+    ("ro.build.stanv.ab", Handler::Plain(modify_ro_build_stanv_ab)),
+];
 
-    return hash;
+#[no_mangle]
+pub extern "C" fn Java_com_windcloud_plugin_mac_utils_ModifyMacUtils_queryParams(
+    env: JNIEnv,
+    _: JClass,
+    param: JString
+) -> jstring {
+
+    let param_str: String = env
+        .get_string(param)
+        .expect("Couldn't get Rust string from Java string")
+        .into();
+
+    info!("Java_com_windcloud_plugin_mac_utils_ModifyMacUtils_queryParams: param = {}", param_str);
+
+    match query_param(&env, &param_str) {
+        Ok(Some(json)) => new_string(&env, json),
+        Ok(None) => {
+            warn!("No reporter for parameter: {}", param_str);
+            JObject::null().into_inner()
+        }
+        Err(e) => {
+            warn!("Failed to query {}: {}", param_str, e);
+            let _ = env.exception_clear();
+            JObject::null().into_inner()
+        }
+    }
 }
 
+/// The reports `startReport` runs when given no parameters.
+const FULL_REPORT: [&str; 15] = [
+    "运行时间",
+    "主屏幕尺寸",
+    "GPS位置信息",
+    "Kernel版本",
+    "CPU信息",
+    "传感器信息",
+    "内存 已用/全部",
+    "储存 已用/全部",
+    "settings get secure android_id",
+    "序列号",
+    "基带",
+    "persist.sys.timezone",
+    "ro.build.date",
+    "pm list packages",
+    "App Install Time",
+];
+
 #[no_mangle]
-pub extern "C" fn Java_com_windcloud_plugin_mac_utils_ModifyMacUtils_modifyParams(
+pub extern "C" fn Java_com_windcloud_plugin_mac_utils_ModifyMacUtils_startReport(
     env: JNIEnv,
     _: JClass,
-    param: JString
+    params: jobjectArray,
+    listener: JObject
 ) -> jint {
 
+    info!("Java_com_windcloud_plugin_mac_utils_ModifyMacUtils_startReport");
+
+    match start_report(&env, params, listener) {
+        Ok(job) => job,
+        Err(e) => {
+            warn!("Failed to start report: {}", e);
+            let _ = env.exception_clear();
+            -1
+        }
+    }
+}
+
+fn start_report(env: &JNIEnv, params: jobjectArray, listener: JObject) -> Result<jint, Box<dyn Error>> {
+    let mut params = string_array(env, params)?;
+    if params.is_empty() {
+        params = FULL_REPORT.iter().map(|param| param.to_string()).collect();
+    }
+    let listener = worker::Listener::new(env, listener)?;
+    worker::spawn("report", listener, move |env, progress| run_report(env, progress, &params))
+}
+
+/// Runs `query_param` for each parameter, reporting progress after each, and
+/// collects the reports under "results" and the failures under "errors".
+fn run_report(env: &JNIEnv, progress: &worker::Progress, params: &[String]) -> Result<String, Box<dyn Error>> {
+    collect_reports(
+        env,
+        params,
+        |param| match query_param(env, param)? {
+            Some(json) => Ok(serde_json::from_str(&json)?),
+            None => Err("no reporter".into()),
+        },
+        |param, done| progress.step(param, done, params.len()),
+    )
+}
+
+/// Runs `report` for each parameter in its own local frame, calling `step`
+/// after each, and returns `{"results": {...}, "errors": {...}}`.
+fn collect_reports<R, S>(env: &JNIEnv, params: &[String], report: R, mut step: S) -> Result<String, Box<dyn Error>>
+where
+    R: Fn(&str) -> Result<serde_json::Value, Box<dyn Error>>,
+    S: FnMut(&str, usize) -> jni::errors::Result<()>,
+{
+    let mut results = serde_json::Map::new();
+    let mut errors = serde_json::Map::new();
+    for (done, param) in params.iter().enumerate() {
+        // Frees the reporter's local references before the next one runs.
+        let outcome = frame::with_local_frame(env, frame::ELEMENT_CAPACITY, || Ok(report(param)))?;
+        match outcome {
+            Ok(value) => {
+                results.insert(param.clone(), value);
+            }
+            Err(e) => {
+                let _ = env.exception_clear();
+                errors.insert(param.clone(), e.to_string().into());
+            }
+        }
+        step(param, done + 1)?;
+    }
+    Ok(to_json(&serde_json::json!({ "results": results, "errors": errors }))?)
+}
+
+#[no_mangle]
+pub extern "C" fn Java_com_windcloud_plugin_mac_utils_ModifyMacUtils_queryMatching(
+    env: JNIEnv,
+    _: JClass,
+    pattern: JString
+) -> jstring {
+
+    let pattern_str: String = env
+        .get_string(pattern)
+        .expect("Couldn't get Rust string from Java string")
+        .into();
+
+    info!("Java_com_windcloud_plugin_mac_utils_ModifyMacUtils_queryMatching: pattern = {}", pattern_str);
+
+    match query_matching(&env, &pattern_str) {
+        Ok(json) => new_string(&env, json),
+        Err(e) => {
+            warn!("Failed to query {}: {}", pattern_str, e);
+            let _ = env.exception_clear();
+            JObject::null().into_inner()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn Java_com_windcloud_plugin_mac_utils_ModifyMacUtils_validateParam(
+    env: JNIEnv,
+    _: JClass,
+    param: JString,
+    value: JString
+) -> jstring {
+
     let param_str: String = env
         .get_string(param)
         .expect("Couldn't get Rust string from Java string")
         .into();
+    let value_str: String = env
+        .get_string(value)
+        .expect("Couldn't get Rust string from Java string")
+        .into();
 
-    info!("lime Java_com_windcloud_plugin_mac_utils_ModifyMacUtils_modifyParams: param = {}", param_str);
+    info!("Java_com_windcloud_plugin_mac_utils_ModifyMacUtils_validateParam: param = {}, value = {}", param_str, value_str);
 
-    match param_str.as_str() {
-        "运行时间" => modify_runtime(),
-        "品牌" => modify_brand(),
-        "型号" => modify_model(),
-        "硬件" => modify_hardware(),
-        "BOARD" => modify_board(),
-        "DISPLAY" => modify_display(),
-        "基带" => modify_baseband(),
-        "序列号" => modify_serial_number(),
-        "主屏幕尺寸" => modify_main_screen_size(),
-        "主屏幕分辨率" => modify_main_screen_resolution(),
-        "GPS位置信息" => modify_gps_location_info(),
-        "内存 已用/全部" => modify_memory_usage(),
-        "储存 已用/全部" => modify_storage_usage(),
-        "SDK INT" => modify_sdk_int(),
-        "RELEASE" => modify_release(),
-        "Version Code" => modify_version_code(),
-        "Bootloader：" => modify_bootloader(),
-        "ART" => modify_art(),
-        "Kernel版本" => modify_kernel_version(),
-        "ro.product.system.brand" => modify_system_brand(),
-        "ro.product.system.device" => modify_system_device(),
-        "ro.product.system.manufacturer" => modify_system_manufacturer(),
-        "ro.product.system.model" => modify_system_model(),
-        "ro.product.system.name" => modify_system_name(),
-        "settings get secure android_id" => modify_android_id(),
-        "getprop ro.serialno" => modify_serial_no(),
-        "传感器信息" => modify_sensor_info(),
-        "Build.ID" => modify_build_id(),
-        "Build.DISPLAY" => modify_build_display(),
-        "Build.PRODUCT" => modify_build_product(),
-        "Build.DEVICE" => modify_build_device(),
-        "Build.BOARD" => modify_build_board(),
-        "Build.CPU_ABI" => modify_build_cpu_abi(),
-        "Build.CPU_ABI2" => modify_build_cpu_abi2(),
-        "Build.MANUFACTURER" => modify_build_manufacturer(),
-        "Build.BRAND" => modify_build_brand(),
-        "Build.MODEL" => modify_build_model(),
-        "Build.BOOTLOADER" => modify_build_bootloader(),
-        "Build.RADIO" => modify_build_radio(),
-        "Build.HARDWARE" => modify_build_hardware(),
-        "Build.SERIAL" => modify_build_serial(),
-        "Build.TYPE" => modify_build_type(),
-        "Build.TAGS" => modify_build_tags(),
-        "Build.FINGERPRINT" => modify_build_fingerprint(),
-        "Build.USER" => modify_build_user(),
-        "Build.HOST" => modify_build_host(),
-        "Build.TIME" => modify_build_time(),
-        "Build.VERSION.INCREMENTAL" => modify_build_version_incremental(),
-        "Build.VERSION.RELEASE" => modify_build_version_release(),
-        "Build.VERSION.RELEASE_OR_CODENAME" => modify_build_version_release_or_codename(),
-        "Build.VERSION.BASE_OS" => modify_build_version_base_os(),
-        "Build.VERSION.SECURITY_PATCH" => modify_build_version_security_patch(),
-        "Build.VERSION.SDK" => modify_build_version_sdk(),
-        "Build.VERSION.CODENAME" => modify_build_version_codename(),
-        "Build.getRadioVersion()" => modify_build_radio_version(),
-        "Build.VERSION.SDK_INT" => modify_build_version_sdk_int(),
-        "Build.VERSION.PREVIEW_SDK_INT" => modify_build_version_preview_sdk_int(),
-        "Build.SUPPORTED_ABIS" => modify_build_supported_abis(),
-        "Build.SUPPORTED_32_BIT_ABIS" => modify_build_supported_32_bit_abis(),
-        "Build.SUPPORTED_64_BIT_ABIS" => modify_build_supported_64_bit_abis(),
-        "Build.getFingerprintedPartitions()" => modify_build_fingerprinted_partitions(),
-        "uname -a" => modify_uname_all(),
-        "uname -r" => modify_uname_release(),
-        "pm list packages" => modify_package_list(),
-        "pm list packages -s" => modify_system_package_list(),
-        "pm list packages -3" => modify_third_party_package_list(),
-        "App Install Time" => modify_app_install_time(),
-        "App Last Update Time" => modify_app_last_update_time(),
-        "ro.system.build.date" => modify_system_build_date(),
-        "ro.system.build.date.utc" => modify_system_build_date_utc(),
-        "ro.system.build.id" => modify_system_build_id(),
-        "ro.system.build.tags" => modify_system_build_tags(),
-        "ro.system.build.type" => modify_system_build_type(),
-        "ro.system.build.version.incremental" => modify_system_build_version_incremental(),
-        "ro.system.build.version.release" => modify_system_build_version_release(),
-        "ro.system.build.version.release_or_codename" => modify_system_build_version_release_or_codename(),
-        "ro.system.build.version.sdk" => modify_system_build_version_sdk(),
-        "ro.build.id" => modify_ro_build_id(),
-        "ro.build.keys" => modify_ro_build_keys(),
-        "ro.build.version.incremental" => modify_ro_build_version_incremental(),
-        "ro.build.version.sdk" => modify_ro_build_version_sdk(),
-        "ro.build.version.preview_sdk" => modify_ro_build_version_preview_sdk(),
-        "ro.build.version.preview_sdk_fingerprint" => modify_ro_build_version_preview_sdk_fingerprint(),
-        "ro.build.version.codename" => modify_ro_build_version_codename(),
-        "ro.build.version.all_codenames" => modify_ro_build_version_all_codenames(),
-        "ro.build.version.release" => modify_ro_build_version_release(),
-        "ro.build.version.release_or_codename" => modify_ro_build_version_release_or_codename(),
-        "ro.build.version.security_patch" => modify_ro_build_version_security_patch(),
-        "ro.build.version.base_os" => modify_ro_build_version_base_os(),
-        "ro.build.version.min_supported_target_sdk" => modify_ro_build_version_min_supported_target_sdk(),
-        "ro.build.date" => modify_ro_build_date(),
-        "ro.build.date.utc" => modify_ro_build_date_utc(),
-        "ro.build.type" => modify_ro_build_type(),
-        "ro.build.user" => modify_ro_build_user(),
-        "ro.build.host" => modify_ro_build_host(),
-        "ro.build.tags" => modify_ro_build_tags(),
-        "ro.build.flavor" => modify_ro_build_flavor(),
-        "ro.build.system_root_image" => modify_ro_build_system_root_image(),
-        "ro.product.cpu.abi" => modify_ro_product_cpu_abi(),
-        "ro.product.cpu.abilist" => modify_ro_product_cpu_abilist(),
-        "ro.product.cpu.abilist32" => modify_ro_product_cpu_abilist32(),
-        "ro.product.cpu.abilist64" => modify_ro_product_cpu_abilist64(),
-        "ro.product.locale" => modify_ro_product_locale(),
-        "ro.wifi.channels" => modify_ro_wifi_channels(),
-        "ro.vendor.product.manufacturer.db" => modify_ro_vendor_product_manufacturer_db(),
-        "ro.vendor.product.device.db" => modify_ro_vendor_product_device_db(),
-        "rild.libpath" => modify_rild_libpath(),
-        "persist.rild.nitz_plmn" => modify_persist_rild_nitz_plmn(),
-        "persist.rild.nitz_long_ons_0" => modify_persist_rild_nitz_long_ons_0(),
-        "persist.rild.nitz_long_ons_1" => modify_persist_rild_nitz_long_ons_1(),
-        "persist.rild.nitz_long_ons_2" => modify_persist_rild_nitz_long_ons_2(),
-        "persist.rild.nitz_long_ons_3" => modify_persist_rild_nitz_long_ons_3(),
-        "persist.rild.nitz_short_ons_0" => modify_persist_rild_nitz_short_ons_0(),
-        "persist.rild.nitz_short_ons_1" => modify_persist_rild_nitz_short_ons_1(),
-        "persist.rild.nitz_short_ons_2" => modify_persist_rild_nitz_short_ons_2(),
-        "persist.rild.nitz_short_ons_3" => modify_persist_rild_nitz_short_ons_3(),
-        "ril.subscription.types" => modify_ril_subscription_types(),
-        "DEVICE_PROVISIONED" => modify_device_provisioned(),
-        "ro.telephony.default_network" => modify_ro_telephony_default_network(),
-        "persist.netmon.linger" => modify_persist_netmon_linger(),
-        "dalvik.vm.heapsize" => modify_dalvik_vm_heapsize(),
-        "dev.pm.dyn_samplingrate" => modify_dev_pm_dyn_samplingrate(),
-        "qcom.hw.aac.encoder" => modify_qcom_hw_aac_encoder(),
-        "persist.vendor.cne.feature" => modify_persist_vendor_cne_feature(),
-        "media.stagefright.enable-player" => modify_media_stagefright_enable_player(),
-        "media.stagefright.enable-http" => modify_media_stagefright_enable_http(),
-        "media.stagefright.enable-aac" => modify_media_stagefright_enable_aac(),
-        "media.stagefright.enable-qcp" => modify_media_stagefright_enable_qcp(),
-        "media.stagefright.enable-fma2dp" => modify_media_stagefright_enable_fma2dp(),
-        "media.stagefright.enable-scan" => modify_media_stagefright_enable_scan(),
-        "media.stagefright.thumbnail.prefer_hw_codecs" => modify_media_stagefright_thumbnail_prefer_hw_codecs(),
-        "mmp.enable.3g2" => modify_mmp_enable_3g2(),
-        "media.aac_51_output_enabled" => modify_media_aac_51_output_enabled(),
-        "media.settings.xml" => modify_media_settings_xml(),
-        "vendor.mm.enable.qcom_parser" => modify_vendor_mm_enable_qcom_parser(),
-        "persist.mm.enable.prefetch" => modify_persist_mm_enable_prefetch(),
-        "ro.netflix.bsp_rev" => modify_ro_netflix_bsp_rev(),
-        "ro.vendor.use_data_netmgrd" => modify_ro_vendor_use_data_netmgrd(),
-        "persist.vendor.data.mode" => modify_persist_vendor_data_mode(),
-        "persist.timed.enable" => modify_persist_timed_enable(),
-        "telephony.lteOnCdmaDevice" => modify_telephony_lte_on_cdma_device(),
-        "persist.fuse_sdcard" => modify_persist_fuse_sdcard(),
-        "ro.bluetooth.library_name" => modify_ro_bluetooth_library_name(),
-        "persist.vendor.btstack.aac_frm_ctl.enabled" => modify_persist_vendor_btstack_aac_frm_ctl_enabled(),
-        "persist.rmnet.data.enable" => modify_persist_rmnet_data_enable(),
-        "persist.data.wda.enable" => modify_persist_data_wda_enable(),
-        "persist.data.df.dl_mode" => modify_persist_data_df_dl_mode(),
-        "persist.data.df.ul_mode" => modify_persist_data_df_ul_mode(),
-        "persist.data.df.agg.dl_pkt" => modify_persist_data_df_agg_dl_pkt(),
-        "persist.data.df.agg.dl_size" => modify_persist_data_df_agg_dl_size(),
-        "persist.data.df.mux_count" => modify_persist_data_df_mux_count(),
-        "persist.data.df.iwlan_mux" => modify_persist_data_df_iwlan_mux(),
-        "persist.data.df.dev_name" => modify_persist_data_df_dev_name(),
-        "persist.debug.wfd.enable" => modify_persist_debug_wfd_enable(),
-        "persist.sys.wfd.virtual" => modify_persist_sys_wfd_virtual(),
-        "debug.sf.enable_hwc_vds" => modify_debug_sf_enable_hwc_vds(),
-        "debug.sf.latch_unsignaled" => modify_debug_sf_latch_unsignaled(),
-        "tunnel.audio.encode" => modify_tunnel_audio_encode(),
-        "use.voice.path.for.pcm.voip" => modify_use_voice_path_for_pcm_voip(),
-        "ro.nfc.port" => modify_ro_nfc_port(),
-        "sys.qca1530" => modify_sys_qca1530(),
-        "persist.debug.coresight.config" => modify_persist_debug_coresight_config(),
-        "ro.hwui.texture_cache_size" => modify_ro_hwui_texture_cache_size(),
-        "ro.hwui.layer_cache_size" => modify_ro_hwui_layer_cache_size(),
-        "ro.hwui.r_buffer_cache_size" => modify_ro_hwui_r_buffer_cache_size(),
-        "ro.hwui.path_cache_size" => modify_ro_hwui_path_cache_size(),
-        "ro.hwui.gradient_cache_size" => modify_ro_hwui_gradient_cache_size(),
-        "ro.hwui.drop_shadow_cache_size" => modify_ro_hwui_drop_shadow_cache_size(),
-        "ro.hwui.texture_cache_flushrate" => modify_ro_hwui_texture_cache_flushrate(),
-        "ro.hwui.text_small_cache_width" => modify_ro_hwui_text_small_cache_width(),
-        "ro.hwui.text_small_cache_height" => modify_ro_hwui_text_small_cache_height(),
-        "ro.hwui.text_large_cache_width" => modify_ro_hwui_text_large_cache_width(),
-        "ro.hwui.text_large_cache_height" => modify_ro_hwui_text_large_cache_height(),
-        "config.disable_rtt" => modify_config_disable_rtt(),
-        "persist.sys.force_sw_gles" => modify_persist_sys_force_sw_gles(),
-        "persist.vendor.radio.atfwd.start" => modify_persist_vendor_radio_atfwd_start(),
-        "ro.kernel.qemu.gles" => modify_ro_kernel_qemu_gles(),
-        "qemu.hw.mainkeys" => modify_qemu_hw_mainkeys(),
-        "vendor.camera.aux.packagelist" => modify_vendor_camera_aux_packagelist(),
-        "persist.vendor.camera.privapp.list" => modify_persist_vendor_camera_privapp_list(),
-        "persist.camera.privapp.list" => modify_persist_camera_privapp_list(),
-        "persist.vendor.overlay.izat.optin" => modify_persist_vendor_overlay_izat_optin(),
-        "persist.backup.ntpServer" => modify_persist_backup_ntp_server(),
-        "persist.vendor.sensors.enable.mag_filter" => modify_persist_vendor_sensors_enable_mag_filter(),
-        "ro.product.property_source_order" => modify_ro_product_property_source_order(),
-        "debug.stagefright.ccodec" => modify_debug_stagefright_ccodec(),
-        "ro.media.recorder-max-base-layer-fps" => modify_ro_media_recorder_max_base_layer_fps(),
-        "ro.charger.enable_suspend" => modify_ro_charger_enable_suspend(),
-        "persist.vendor.btstack.enable.twsplus" => modify_persist_vendor_btstack_enable_twsplus(),
-        "persist.vendor.bt.a2dp.hal.implementation" => modify_persist_vendor_bt_a2dp_hal_implementation(),
-        "persist.vendor.naruto.light.support" => modify_persist_vendor_naruto_light_support(),
-        "ro.apex.updatable" => modify_ro_apex_updatable(),
-        "persist.vendor.btstack.enable.lpa" => modify_persist_vendor_btstack_enable_lpa(),
-        "ro.audio.monitorRotation" => modify_ro_audio_monitor_rotation(),
-        "persist.camera.assert.panic" => modify_persist_camera_assert_panic(),
-        "ro.opcamera.support" => modify_ro_opcamera_support(),
-        "persist.vendor.ims.disableADBLogs" => modify_persist_vendor_ims_disable_adb_logs(),
-        "ro.gfx.driver.0" => modify_ro_gfx_driver_0(),
-        "ro.gfx.driver.1" => modify_ro_gfx_driver_1(),
-        "ro.treble.enabled" => modify_ro_treble_enabled(),
-        "net.bt.name" => modify_net_bt_name(),
-        "ro.vendor.qti.va_aosp.support" => modify_ro_vendor_qti_va_aosp_support(),
-        "ro.system.build.fingerprint" => modify_ro_system_build_fingerprint(),
-        "ro.product.system.brand" => modify_ro_product_system_brand(),
-        "ro.product.system.device" => modify_ro_product_system_device(),
-        "ro.product.system.manufacturer" => modify_ro_product_system_manufacturer(),
-        "ro.product.system.name" => modify_ro_product_system_name(),
-        "ro.product.build.fingerprint" => modify_ro_product_build_fingerprint(),
-        "ro.product.product.brand" => modify_ro_product_product_brand(),
-        "ro.product.product.device" => modify_ro_product_product_device(),
-        "ro.product.product.manufacturer" => modify_ro_product_product_manufacturer(),
-        "ro.product.product.name" => modify_ro_product_product_name(),
-        "ro.build.region" => modify_ro_build_region(),
-        "persist.sys.kernel" => modify_persist_sys_kernel(),
-        "persist.sys.main" => modify_persist_sys_main(),
-        "persist.sys.system" => modify_persist_sys_system(),
-        "persist.sys.radio" => modify_persist_sys_radio(),
-        "persist.sys.event" => modify_persist_sys_event(),
-        "persist.sys.perf" => modify_persist_sys_perf(),
-        "persist.sys.crash" => modify_persist_sys_crash(),
-        "persist.sys.qxdm" => modify_persist_sys_qxdm(),
-        "debug.sf.dump.primary" => modify_debug_sf_dump_primary(),
-        "debug.sf.dump.external" => modify_debug_sf_dump_external(),
-        "debug.sf.dump.enable" => modify_debug_sf_dump_enable(),
-        "debug.sf.dump" => modify_debug_sf_dump(),
-        "persist.sys.qsee" => modify_persist_sys_qsee(),
-        "persist.sys.tz" => modify_persist_sys_tz(),
-        "persist.sys.bootloader" => modify_persist_sys_bootloader(),
-        "persist.sys.tcpdump.logsize" => modify_persist_sys_tcpdump_logsize(),
-        "persist.sys.tcpdump.lognum" => modify_persist_sys_tcpdump_lognum(),
-        "persist.log.tag.FuseDaemon" => modify_persist_log_tag_fusedaemon(),
-        "persist.sys.assert.panic" => modify_persist_sys_assert_panic(),
-        "persist.sys.assert.enable" => modify_persist_sys_assert_enable(),
-        "persist.sys.cfu_auto" => modify_persist_sys_cfu_auto(),
-        "ro.imei.check" => modify_ro_imei_check(),
-        "ro.vendor.custom.image" => modify_ro_vendor_custom_image(),
-        "ro.vendor.update.india" => modify_ro_vendor_update_india(),
-        "ro.build.os_type" => modify_ro_build_os_type(),
-        "persist.sys.oem.region" => modify_persist_sys_oem_region(),
-        "ro.build.real_device" => modify_ro_build_real_device(),
-        "ro.build.product" => modify_ro_build_product(),
-        "ro.product.device" => modify_ro_product_device(),
-        "ro.build.date.Ymd" => modify_ro_build_date_ymd(),
-        "ro.build.date.ymd" => modify_ro_build_date_ymd_lowercase(),
-        "ro.build.date.YmdHM" => modify_ro_build_date_ymdhm(),
-        "ro.build.user" => modify_ro_build_user2(),
-        "ro.build.flavor" => modify_ro_build_flavor2(),
-        "ro.build.description" => modify_ro_build_description(),
-        "ro.common.soft" => modify_ro_common_soft(),
-        "ro.build.release_type" => modify_ro_build_release_type(),
-        "ro.build.soft.version" => modify_ro_build_soft_version(),
-        "ro.xxversion" => modify_ro_xxversion(),
-        "ro.build.kernel.id" => modify_ro_build_kernel_id(),
-        "ro.display.series" => modify_ro_display_series(),
-        "ro.build.ota.versionname" => modify_ro_build_ota_versionname(),
-        "ro.build.version.ota" => modify_ro_build_version_ota(),
-        "ro.build.soft.majorversion" => modify_ro_build_soft_majorversion(),
-        "ro.product.brand" => modify_ro_product_brand(),
-        "ro.product.manufacturer" => modify_ro_product_manufacturer(),
-        "persist.sys.timezone" => modify_persist_sys_timezone(),
-        "ro.rom.version" => modify_ro_rom_version(),
-        "persist.vendor.ssr.enable_ramdumps" => modify_persist_vendor_ssr_enable_ramdumps(),
-        "ro.build.stanv.ab" => modify_ro_build_stanv_ab(),
-        _ => {
-            warn!("Unknown parameter: {}", param_str);
-            0
+    match registry::check_set_current(&param_str, &value_str) {
+        Ok(kind) => {
+            info!("{} may be set: {:?}", param_str, kind);
+            JObject::null().into_inner()
+        }
+        Err(e) => {
+            warn!("Rejected {}: {}", param_str, e);
+            new_string(&env, e.to_string())
+        }
+    }
+}
+
+/// The registry keys matching a glob pattern with their current values: the
+/// report when the key has one, otherwise the property of that name with its
+/// SELinux context, or null for keys that are not properties.
+fn query_matching(env: &JNIEnv, pattern: &str) -> Result<String, Box<dyn Error>> {
+    let keys = registry::matching(pattern);
+    collect_reports(
+        env,
+        &keys,
+        |key| match query_param(env, key)? {
+            Some(json) => Ok(serde_json::from_str(&json)?),
+            None if registry::is_property(key) => {
+                Ok(serde_json::to_value(property_contexts::PropertyValue::read(key))?)
+            }
+            None => Ok(serde_json::Value::Null),
+        },
+        |_, _| Ok(()),
+    )
+}
+
+/// A `String[]`, empty when `array` is null.
+fn string_array(env: &JNIEnv, array: jobjectArray) -> jni::errors::Result<Vec<String>> {
+    frame::map_array(env, array, |element| Ok(env.get_string(JString::from(element))?.into()))
+}
+
+/// Runs the reporter behind `param` and serializes its result to JSON.
+/// Parameters without their own arm are tried as shell commands (`getprop`,
+/// `settings get`, `uname`, `pm list packages`). Returns `Ok(None)` for
+/// parameters that have no reporter yet.
+fn query_param(env: &JNIEnv, param: &str) -> Result<Option<String>, Box<dyn Error>> {
+    let json = match param {
+        "主屏幕尺寸" | "主屏幕分辨率" => to_json(&display::DisplayReport::from(display::DisplayInfo::current(env)?))?,
+        "GPS位置信息" => to_json(&location::LocationReport::current(env)?)?,
+        "运行时间" => to_json(&uptime::Runtime::read()?)?,
+        "Kernel版本" => to_json(&kernel::KernelInfo::read()?)?,
+        "Build.CPU_ABI" => to_json(&cpu::AbiInfo::read().cpu_abi)?,
+        "Build.CPU_ABI2" => to_json(&cpu::AbiInfo::read().cpu_abi2)?,
+        "Build.SUPPORTED_ABIS" => to_json(&cpu::AbiInfo::read().supported_abis)?,
+        "Build.SUPPORTED_32_BIT_ABIS" => to_json(&cpu::AbiInfo::read().supported_32_bit_abis)?,
+        "Build.SUPPORTED_64_BIT_ABIS" => to_json(&cpu::AbiInfo::read().supported_64_bit_abis)?,
+        "ro.product.cpu.abi" => to_json(&cpu::AbiInfo::read().cpu_abi)?,
+        "ro.product.cpu.abilist" => to_json(&cpu::AbiInfo::read().supported_abis.join(","))?,
+        "ro.product.cpu.abilist32" => to_json(&cpu::AbiInfo::read().supported_32_bit_abis.join(","))?,
+        "ro.product.cpu.abilist64" => to_json(&cpu::AbiInfo::read().supported_64_bit_abis.join(","))?,
+        "传感器信息" => to_json(&sensors::list_with_details(env)?)?,
+        "CPU信息" => to_json(&cpu::CpuInfo::read()?)?,
+        "App Install Time" | "App Last Update Time" => to_json(&packages::InstallTimes::current(env)?)?,
+        "settings get secure android_id" => to_json(&identifiers::android_id(env)?)?,
+        "getprop ro.serialno" => to_json(&identifiers::ro_serialno())?,
+        "Build.SERIAL" => to_json(&identifiers::build_serial(env)?)?,
+        "序列号" => to_json(&identifiers::SerialReport::current(env)?)?,
+        "基带" => to_json(&radio::RadioInfo::current(env)?)?,
+        "Build.RADIO" => to_json(&radio::build_radio(env)?)?,
+        "Build.getRadioVersion()" => to_json(&radio::radio_version(env)?)?,
+        "rild.libpath"
+        | "persist.rild.nitz_plmn"
+        | "persist.rild.nitz_long_ons_0"
+        | "persist.rild.nitz_long_ons_1"
+        | "persist.rild.nitz_long_ons_2"
+        | "persist.rild.nitz_long_ons_3"
+        | "persist.rild.nitz_short_ons_0"
+        | "persist.rild.nitz_short_ons_1"
+        | "persist.rild.nitz_short_ons_2"
+        | "persist.rild.nitz_short_ons_3"
+        | "ril.subscription.types" => to_json(&property_contexts::PropertyValue::read(param))?,
+        "persist.sys.timezone" | "persist.sys.tz" | "ro.product.locale" => {
+            to_json(&locale::LocaleReport::current(env)?)?
+        }
+        "Build.TIME"
+        | "ro.build.date"
+        | "ro.build.date.utc"
+        | "ro.build.date.Ymd"
+        | "ro.build.date.ymd"
+        | "ro.build.date.YmdHM"
+        | "ro.system.build.date"
+        | "ro.system.build.date.utc" => to_json(&build_date::BuildDateReport::current(env)?)?,
+        "property_contexts" => to_json(&property_contexts::PropertyContexts::system().registry_report())?,
+        "内存 已用/全部" => to_json(&usage::memory_usage()?)?,
+        "储存 已用/全部" => to_json(&usage::data_storage_usage()?)?,
+        _ => match command::Command::parse(param) {
+            Ok(command) => to_json(&command.run(env)?)?,
+            Err(command::ParseError::NotACommand) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        },
+    };
+    Ok(Some(json))
+}
+
+fn to_json<T: Serialize>(value: &T) -> serde_json::Result<String> {
+    serde_json::to_string(value)
+}
+
+/// `value` as a Java string for an export to return, or null when it cannot
+/// be created, e.g. with an exception still pending.
+fn new_string(env: &JNIEnv, value: String) -> jstring {
+    match env.new_string(value) {
+        Ok(value) => value.into_inner(),
+        Err(e) => {
+            warn!("Failed to create the result string: {}", e);
+            let _ = env.exception_clear();
+            JObject::null().into_inner()
+        }
+    }
+}
+
+/// The error the file parsers return for input that is not in the expected format.
+pub(crate) fn invalid_data(message: String) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+/// Logs the outcome of a reporter-backed handler: 1 on success, -1 on failure.
+fn report<T: Debug, E: Debug>(name: &str, result: Result<T, E>) -> jint {
+    match result {
+        Ok(value) => {
+            info!("{}: {:?}", name, value);
+            1
+        }
+        Err(e) => {
+            warn!("{} failed: {:?}", name, e);
+            -1
         }
     }
 }
 
+/// `report` for reporters that cannot fail.
+fn report_value<T: Debug>(name: &str, value: T) -> jint {
+    info!("{}: {:?}", name, value);
+    1
+}
+
+/// Fallback for parameters without their own arm: runs them as a shell
+/// command when they parse as one.
+fn modify_command(env: &JNIEnv, param: &str) -> jint {
+    match command::Command::parse(param) {
+        Ok(command) => {
+            info!("Executing command {:?}", command);
+            report(param, command.run(env))
+        }
+        Err(command::ParseError::NotACommand) => {
+            warn!("Unknown parameter: {}", param);
+            0
+        }
+        Err(e) => {
+            warn!("{}: {}", param, e);
+            -1
+        }
+    }
+}
 
-fn modify_runtime() -> jint { info!("Executing modify_runtime"); 0 }
+fn modify_runtime() -> jint { info!("Executing modify_runtime"); report("运行时间", uptime::Runtime::read()) }
 fn modify_brand() -> jint { info!("Executing modify_brand"); 0 }
 fn modify_model() -> jint { info!("Executing modify_model"); 0 }
 fn modify_hardware() -> jint { info!("Executing modify_hardware"); 0 }
 fn modify_board() -> jint { info!("Executing modify_board"); 0 }
 fn modify_display() -> jint { info!("Executing modify_display"); 0 }
-fn modify_baseband() -> jint { info!("Executing modify_baseband"); 0 }
-fn modify_serial_number() -> jint { info!("Executing modify_serial_number"); 0 }
-fn modify_main_screen_size() -> jint { info!("Executing modify_main_screen_size"); 0 }
-fn modify_main_screen_resolution() -> jint { info!("Executing modify_main_screen_resolution"); 0 }
-fn modify_gps_location_info() -> jint { info!("Executing modify_gps_location_info"); 0 }
-fn modify_memory_usage() -> jint { info!("Executing modify_memory_usage"); 0 }
-fn modify_storage_usage() -> jint { info!("Executing modify_storage_usage"); 0 }
+fn modify_baseband() -> jint { info!("Executing modify_baseband"); report_value("基带", radio::baseband()) }
+fn modify_serial_number(env: &JNIEnv) -> jint { info!("Executing modify_serial_number"); report("序列号", identifiers::SerialReport::current(env)) }
+fn modify_main_screen_size(env: &JNIEnv) -> jint { info!("Executing modify_main_screen_size"); report("主屏幕尺寸", query_param(env, "主屏幕尺寸")) }
+fn modify_main_screen_resolution(env: &JNIEnv) -> jint { info!("Executing modify_main_screen_resolution"); report("主屏幕分辨率", query_param(env, "主屏幕分辨率")) }
+fn modify_gps_location_info(env: &JNIEnv) -> jint { info!("Executing modify_gps_location_info"); report("GPS位置信息", location::LocationReport::current(env)) }
+fn modify_memory_usage() -> jint { info!("Executing modify_memory_usage"); report("内存 已用/全部", usage::memory_usage()) }
+fn modify_storage_usage() -> jint { info!("Executing modify_storage_usage"); report("储存 已用/全部", usage::data_storage_usage()) }
 fn modify_sdk_int() -> jint { info!("Executing modify_sdk_int"); 0 }
 fn modify_release() -> jint { info!("Executing modify_release"); 0 }
 fn modify_version_code() -> jint { info!("Executing modify_version_code"); 0 }
 fn modify_bootloader() -> jint { info!("Executing modify_bootloader"); 0 }
 fn modify_art() -> jint { info!("Executing modify_art"); 0 }
-fn modify_kernel_version() -> jint { info!("Executing modify_kernel_version"); 0 }
+fn modify_kernel_version() -> jint { info!("Executing modify_kernel_version"); report("Kernel版本", kernel::KernelInfo::read()) }
 fn modify_system_brand() -> jint { info!("Executing modify_system_brand"); 0 }
 fn modify_system_device() -> jint { info!("Executing modify_system_device"); 0 }
 fn modify_system_manufacturer() -> jint { info!("Executing modify_system_manufacturer"); 0 }
 fn modify_system_model() -> jint { info!("Executing modify_system_model"); 0 }
 fn modify_system_name() -> jint { info!("Executing modify_system_name"); 0 }
-fn modify_android_id() -> jint { info!("Executing modify_android_id"); 0 }
-fn modify_serial_no() -> jint { info!("Executing modify_serial_no"); 0 }
-fn modify_sensor_info() -> jint { info!("Executing modify_sensor_info"); 0 }
+fn modify_android_id(env: &JNIEnv) -> jint { info!("Executing modify_android_id"); report("settings get secure android_id", identifiers::android_id(env)) }
+fn modify_serial_no() -> jint { info!("Executing modify_serial_no"); report_value("getprop ro.serialno", identifiers::ro_serialno()) }
+fn modify_sensor_info(env: &JNIEnv) -> jint { info!("Executing modify_sensor_info"); report("传感器信息", sensors::list_with_details(env)) }
+fn modify_cpu_info() -> jint { info!("Executing modify_cpu_info"); report("CPU信息", cpu::CpuInfo::read()) }
 fn modify_build_id() -> jint { info!("Executing modify_build_id"); 0 }
 fn modify_build_display() -> jint { info!("Executing modify_build_display"); 0 }
 fn modify_build_product() -> jint { info!("Executing modify_build_product"); 0 }
 fn modify_build_device() -> jint { info!("Executing modify_build_device"); 0 }
 fn modify_build_board() -> jint { info!("Executing modify_build_board"); 0 }
-fn modify_build_cpu_abi() -> jint { info!("Executing modify_build_cpu_abi"); 0 }
-fn modify_build_cpu_abi2() -> jint { info!("Executing modify_build_cpu_abi2"); 0 }
+fn modify_build_cpu_abi() -> jint { info!("Executing modify_build_cpu_abi"); report_value("Build.CPU_ABI", cpu::AbiInfo::read().cpu_abi) }
+fn modify_build_cpu_abi2() -> jint { info!("Executing modify_build_cpu_abi2"); report_value("Build.CPU_ABI2", cpu::AbiInfo::read().cpu_abi2) }
 fn modify_build_manufacturer() -> jint { info!("Executing modify_build_manufacturer"); 0 }
 fn modify_build_brand() -> jint { info!("Executing modify_build_brand"); 0 }
 fn modify_build_model() -> jint { info!("Executing modify_build_model"); 0 }
 fn modify_build_bootloader() -> jint { info!("Executing modify_build_bootloader"); 0 }
-fn modify_build_radio() -> jint { info!("Executing modify_build_radio"); 0 }
+fn modify_build_radio(env: &JNIEnv) -> jint { info!("Executing modify_build_radio"); report("Build.RADIO", radio::build_radio(env)) }
 fn modify_build_hardware() -> jint { info!("Executing modify_build_hardware"); 0 }
-fn modify_build_serial() -> jint { info!("Executing modify_build_serial"); 0 }
+fn modify_build_serial(env: &JNIEnv) -> jint { info!("Executing modify_build_serial"); report("Build.SERIAL", identifiers::build_serial(env)) }
 fn modify_build_type() -> jint { info!("Executing modify_build_type"); 0 }
 fn modify_build_tags() -> jint { info!("Executing modify_build_tags"); 0 }
 fn modify_build_fingerprint() -> jint { info!("Executing modify_build_fingerprint"); 0 }
 fn modify_build_user() -> jint { info!("Executing modify_build_user"); 0 }
 fn modify_build_host() -> jint { info!("Executing modify_build_host"); 0 }
-fn modify_build_time() -> jint { info!("Executing modify_build_time"); 0 }
+fn modify_build_time(env: &JNIEnv) -> jint { info!("Executing modify_build_time"); report("Build.TIME", build_date::BuildDateReport::current(env)) }
 fn modify_build_version_incremental() -> jint { info!("Executing modify_build_version_incremental"); 0 }
 fn modify_build_version_release() -> jint { info!("Executing modify_build_version_release"); 0 }
 fn modify_build_version_release_or_codename() -> jint { info!("Executing modify_build_version_release_or_codename"); 0 }
@@ -412,22 +761,17 @@ fn modify_build_version_base_os() -> jint { info!("Executing modify_build_versio
 fn modify_build_version_security_patch() -> jint { info!("Executing modify_build_version_security_patch"); 0 }
 fn modify_build_version_sdk() -> jint { info!("Executing modify_build_version_sdk"); 0 }
 fn modify_build_version_codename() -> jint { info!("Executing modify_build_version_codename"); 0 }
-fn modify_build_radio_version() -> jint { info!("Executing modify_build_radio_version"); 0 }
+fn modify_build_radio_version(env: &JNIEnv) -> jint { info!("Executing modify_build_radio_version"); report("Build.getRadioVersion()", radio::radio_version(env)) }
 fn modify_build_version_sdk_int() -> jint { info!("Executing modify_build_version_sdk_int"); 0 }
 fn modify_build_version_preview_sdk_int() -> jint { info!("Executing modify_build_version_preview_sdk_int"); 0 }
-fn modify_build_supported_abis() -> jint { info!("Executing modify_build_supported_abis"); 0 }
-fn modify_build_supported_32_bit_abis() -> jint { info!("Executing modify_build_supported_32_bit_abis"); 0 }
-fn modify_build_supported_64_bit_abis() -> jint { info!("Executing modify_build_supported_64_bit_abis"); 0 }
+fn modify_build_supported_abis() -> jint { info!("Executing modify_build_supported_abis"); report_value("Build.SUPPORTED_ABIS", cpu::AbiInfo::read().supported_abis) }
+fn modify_build_supported_32_bit_abis() -> jint { info!("Executing modify_build_supported_32_bit_abis"); report_value("Build.SUPPORTED_32_BIT_ABIS", cpu::AbiInfo::read().supported_32_bit_abis) }
+fn modify_build_supported_64_bit_abis() -> jint { info!("Executing modify_build_supported_64_bit_abis"); report_value("Build.SUPPORTED_64_BIT_ABIS", cpu::AbiInfo::read().supported_64_bit_abis) }
 fn modify_build_fingerprinted_partitions() -> jint { info!("Executing modify_build_fingerprinted_partitions"); 0 }
-fn modify_uname_all() -> jint { info!("Executing modify_uname_all"); 0 }
-fn modify_uname_release() -> jint { info!("Executing modify_uname_release"); 0 }
-fn modify_package_list() -> jint { info!("Executing modify_package_list"); 0 }
-fn modify_system_package_list() -> jint { info!("Executing modify_system_package_list"); 0 }
-fn modify_third_party_package_list() -> jint { info!("Executing modify_third_party_package_list"); 0 }
-fn modify_app_install_time() -> jint { info!("Executing modify_app_install_time"); 0 }
-fn modify_app_last_update_time() -> jint { info!("Executing modify_app_last_update_time"); 0 }
-fn modify_system_build_date() -> jint { info!("Executing modify_system_build_date"); 0 }
-fn modify_system_build_date_utc() -> jint { info!("Executing modify_system_build_date_utc"); 0 }
+fn modify_app_install_time(env: &JNIEnv) -> jint { info!("Executing modify_app_install_time"); report("App Install Time", packages::InstallTimes::current(env).map(|times| times.first_install)) }
+fn modify_app_last_update_time(env: &JNIEnv) -> jint { info!("Executing modify_app_last_update_time"); report("App Last Update Time", packages::InstallTimes::current(env).map(|times| times.last_update)) }
+fn modify_system_build_date(env: &JNIEnv) -> jint { info!("Executing modify_system_build_date"); report("ro.system.build.date", build_date::BuildDateReport::current(env)) }
+fn modify_system_build_date_utc(env: &JNIEnv) -> jint { info!("Executing modify_system_build_date_utc"); report("ro.system.build.date.utc", build_date::BuildDateReport::current(env)) }
 fn modify_system_build_id() -> jint { info!("Executing modify_system_build_id"); 0 }
 fn modify_system_build_tags() -> jint { info!("Executing modify_system_build_tags"); 0 }
 fn modify_system_build_type() -> jint { info!("Executing modify_system_build_type"); 0 }
@@ -448,33 +792,33 @@ fn modify_ro_build_version_release_or_codename() -> jint { info!("Executing modi
 fn modify_ro_build_version_security_patch() -> jint { info!("Executing modify_ro_build_version_security_patch"); 0 }
 fn modify_ro_build_version_base_os() -> jint { info!("Executing modify_ro_build_version_base_os"); 0 }
 fn modify_ro_build_version_min_supported_target_sdk() -> jint { info!("Executing modify_ro_build_version_min_supported_target_sdk"); 0 }
-fn modify_ro_build_date() -> jint { info!("Executing modify_ro_build_date"); 0 }
-fn modify_ro_build_date_utc() -> jint { info!("Executing modify_ro_build_date_utc"); 0 }
+fn modify_ro_build_date(env: &JNIEnv) -> jint { info!("Executing modify_ro_build_date"); report("ro.build.date", build_date::BuildDateReport::current(env)) }
+fn modify_ro_build_date_utc(env: &JNIEnv) -> jint { info!("Executing modify_ro_build_date_utc"); report("ro.build.date.utc", build_date::BuildDateReport::current(env)) }
 fn modify_ro_build_type() -> jint { info!("Executing modify_ro_build_type"); 0 }
 fn modify_ro_build_user() -> jint { info!("Executing modify_ro_build_user"); 0 }
 fn modify_ro_build_host() -> jint { info!("Executing modify_ro_build_host"); 0 }
 fn modify_ro_build_tags() -> jint { info!("Executing modify_ro_build_tags"); 0 }
 fn modify_ro_build_flavor() -> jint { info!("Executing modify_ro_build_flavor"); 0 }
 fn modify_ro_build_system_root_image() -> jint { info!("Executing modify_ro_build_system_root_image"); 0 }
-fn modify_ro_product_cpu_abi() -> jint { info!("Executing modify_ro_product_cpu_abi"); 0 }
-fn modify_ro_product_cpu_abilist() -> jint { info!("Executing modify_ro_product_cpu_abilist"); 0 }
-fn modify_ro_product_cpu_abilist32() -> jint { info!("Executing modify_ro_product_cpu_abilist32"); 0 }
-fn modify_ro_product_cpu_abilist64() -> jint { info!("Executing modify_ro_product_cpu_abilist64"); 0 }
-fn modify_ro_product_locale() -> jint { info!("Executing modify_ro_product_locale"); 0 }
+fn modify_ro_product_cpu_abi() -> jint { info!("Executing modify_ro_product_cpu_abi"); report_value("ro.product.cpu.abi", cpu::AbiInfo::read().cpu_abi) }
+fn modify_ro_product_cpu_abilist() -> jint { info!("Executing modify_ro_product_cpu_abilist"); report_value("ro.product.cpu.abilist", cpu::AbiInfo::read().supported_abis.join(",")) }
+fn modify_ro_product_cpu_abilist32() -> jint { info!("Executing modify_ro_product_cpu_abilist32"); report_value("ro.product.cpu.abilist32", cpu::AbiInfo::read().supported_32_bit_abis.join(",")) }
+fn modify_ro_product_cpu_abilist64() -> jint { info!("Executing modify_ro_product_cpu_abilist64"); report_value("ro.product.cpu.abilist64", cpu::AbiInfo::read().supported_64_bit_abis.join(",")) }
+fn modify_ro_product_locale(env: &JNIEnv) -> jint { info!("Executing modify_ro_product_locale"); report("ro.product.locale", locale::LocaleReport::current(env)) }
 fn modify_ro_wifi_channels() -> jint { info!("Executing modify_ro_wifi_channels"); 0 }
 fn modify_ro_vendor_product_manufacturer_db() -> jint { info!("Executing modify_ro_vendor_product_manufacturer_db"); 0 }
 fn modify_ro_vendor_product_device_db() -> jint { info!("Executing modify_ro_vendor_product_device_db"); 0 }
-fn modify_rild_libpath() -> jint { info!("Executing modify_rild_libpath"); 0 }
-fn modify_persist_rild_nitz_plmn() -> jint { info!("Executing modify_persist_rild_nitz_plmn"); 0 }
-fn modify_persist_rild_nitz_long_ons_0() -> jint { info!("Executing modify_persist_rild_nitz_long_ons_0"); 0 }
-fn modify_persist_rild_nitz_long_ons_1() -> jint { info!("Executing modify_persist_rild_nitz_long_ons_1"); 0 }
-fn modify_persist_rild_nitz_long_ons_2() -> jint { info!("Executing modify_persist_rild_nitz_long_ons_2"); 0 }
-fn modify_persist_rild_nitz_long_ons_3() -> jint { info!("Executing modify_persist_rild_nitz_long_ons_3"); 0 }
-fn modify_persist_rild_nitz_short_ons_0() -> jint { info!("Executing modify_persist_rild_nitz_short_ons_0"); 0 }
-fn modify_persist_rild_nitz_short_ons_1() -> jint { info!("Executing modify_persist_rild_nitz_short_ons_1"); 0 }
-fn modify_persist_rild_nitz_short_ons_2() -> jint { info!("Executing modify_persist_rild_nitz_short_ons_2"); 0 }
-fn modify_persist_rild_nitz_short_ons_3() -> jint { info!("Executing modify_persist_rild_nitz_short_ons_3"); 0 }
-fn modify_ril_subscription_types() -> jint { info!("Executing modify_ril_subscription_types"); 0 }
+fn modify_rild_libpath() -> jint { info!("Executing modify_rild_libpath"); report_value("rild.libpath", props::get("rild.libpath")) }
+fn modify_persist_rild_nitz_plmn() -> jint { info!("Executing modify_persist_rild_nitz_plmn"); report_value("persist.rild.nitz_plmn", props::get("persist.rild.nitz_plmn")) }
+fn modify_persist_rild_nitz_long_ons_0() -> jint { info!("Executing modify_persist_rild_nitz_long_ons_0"); report_value("persist.rild.nitz_long_ons_0", props::get("persist.rild.nitz_long_ons_0")) }
+fn modify_persist_rild_nitz_long_ons_1() -> jint { info!("Executing modify_persist_rild_nitz_long_ons_1"); report_value("persist.rild.nitz_long_ons_1", props::get("persist.rild.nitz_long_ons_1")) }
+fn modify_persist_rild_nitz_long_ons_2() -> jint { info!("Executing modify_persist_rild_nitz_long_ons_2"); report_value("persist.rild.nitz_long_ons_2", props::get("persist.rild.nitz_long_ons_2")) }
+fn modify_persist_rild_nitz_long_ons_3() -> jint { info!("Executing modify_persist_rild_nitz_long_ons_3"); report_value("persist.rild.nitz_long_ons_3", props::get("persist.rild.nitz_long_ons_3")) }
+fn modify_persist_rild_nitz_short_ons_0() -> jint { info!("Executing modify_persist_rild_nitz_short_ons_0"); report_value("persist.rild.nitz_short_ons_0", props::get("persist.rild.nitz_short_ons_0")) }
+fn modify_persist_rild_nitz_short_ons_1() -> jint { info!("Executing modify_persist_rild_nitz_short_ons_1"); report_value("persist.rild.nitz_short_ons_1", props::get("persist.rild.nitz_short_ons_1")) }
+fn modify_persist_rild_nitz_short_ons_2() -> jint { info!("Executing modify_persist_rild_nitz_short_ons_2"); report_value("persist.rild.nitz_short_ons_2", props::get("persist.rild.nitz_short_ons_2")) }
+fn modify_persist_rild_nitz_short_ons_3() -> jint { info!("Executing modify_persist_rild_nitz_short_ons_3"); report_value("persist.rild.nitz_short_ons_3", props::get("persist.rild.nitz_short_ons_3")) }
+fn modify_ril_subscription_types() -> jint { info!("Executing modify_ril_subscription_types"); report_value("ril.subscription.types", props::get("ril.subscription.types")) }
 fn modify_device_provisioned() -> jint { info!("Executing modify_device_provisioned"); 0 }
 fn modify_ro_telephony_default_network() -> jint { info!("Executing modify_ro_telephony_default_network"); 0 }
 fn modify_persist_netmon_linger() -> jint { info!("Executing modify_persist_netmon_linger"); 0 }
@@ -561,10 +905,6 @@ fn modify_ro_treble_enabled() -> jint { info!("Executing modify_ro_treble_enable
 fn modify_net_bt_name() -> jint { info!("Executing modify_net_bt_name"); 0 }
 fn modify_ro_vendor_qti_va_aosp_support() -> jint { info!("Executing modify_ro_vendor_qti_va_aosp_support"); 0 }
 fn modify_ro_system_build_fingerprint() -> jint { info!("Executing modify_ro_system_build_fingerprint"); 0 }
-fn modify_ro_product_system_brand() -> jint { info!("Executing modify_ro_product_system_brand"); 0 }
-fn modify_ro_product_system_device() -> jint { info!("Executing modify_ro_product_system_device"); 0 }
-fn modify_ro_product_system_manufacturer() -> jint { info!("Executing modify_ro_product_system_manufacturer"); 0 }
-fn modify_ro_product_system_name() -> jint { info!("Executing modify_ro_product_system_name"); 0 }
 fn modify_ro_product_build_fingerprint() -> jint { info!("Executing modify_ro_product_build_fingerprint"); 0 }
 fn modify_ro_product_product_brand() -> jint { info!("Executing modify_ro_product_product_brand"); 0 }
 fn modify_ro_product_product_device() -> jint { info!("Executing modify_ro_product_product_device"); 0 }
@@ -584,7 +924,7 @@ fn modify_debug_sf_dump_external() -> jint { info!("Executing modify_debug_sf_du
 fn modify_debug_sf_dump_enable() -> jint { info!("Executing modify_debug_sf_dump_enable"); 0 }
 fn modify_debug_sf_dump() -> jint { info!("Executing modify_debug_sf_dump"); 0 }
 fn modify_persist_sys_qsee() -> jint { info!("Executing modify_persist_sys_qsee"); 0 }
-fn modify_persist_sys_tz() -> jint { info!("Executing modify_persist_sys_tz"); 0 }
+fn modify_persist_sys_tz(env: &JNIEnv) -> jint { info!("Executing modify_persist_sys_tz"); report("persist.sys.tz", locale::LocaleReport::current(env)) }
 fn modify_persist_sys_bootloader() -> jint { info!("Executing modify_persist_sys_bootloader"); 0 }
 fn modify_persist_sys_tcpdump_logsize() -> jint { info!("Executing modify_persist_sys_tcpdump_logsize"); 0 }
 fn modify_persist_sys_tcpdump_lognum() -> jint { info!("Executing modify_persist_sys_tcpdump_lognum"); 0 }
@@ -600,11 +940,9 @@ fn modify_persist_sys_oem_region() -> jint { info!("Executing modify_persist_sys
 fn modify_ro_build_real_device() -> jint { info!("Executing modify_ro_build_real_device"); 0 }
 fn modify_ro_build_product() -> jint { info!("Executing modify_ro_build_product"); 0 }
 fn modify_ro_product_device() -> jint { info!("Executing modify_ro_product_device"); 0 }
-fn modify_ro_build_date_ymd() -> jint { info!("Executing modify_ro_build_date_ymd"); 0 }
-fn modify_ro_build_date_ymd_lowercase() -> jint { info!("Executing modify_ro_build_date_ymd_lowercase"); 0 }
-fn modify_ro_build_date_ymdhm() -> jint { info!("Executing modify_ro_build_date_ymdhm"); 0 }
-fn modify_ro_build_user2() -> jint { info!("Executing modify_ro_build_user2"); 0 }
-fn modify_ro_build_flavor2() -> jint { info!("Executing modify_ro_build_flavor2"); 0 }
+fn modify_ro_build_date_ymd(env: &JNIEnv) -> jint { info!("Executing modify_ro_build_date_ymd"); report("ro.build.date.Ymd", build_date::BuildDateReport::current(env)) }
+fn modify_ro_build_date_ymd_lowercase(env: &JNIEnv) -> jint { info!("Executing modify_ro_build_date_ymd_lowercase"); report("ro.build.date.ymd", build_date::BuildDateReport::current(env)) }
+fn modify_ro_build_date_ymdhm(env: &JNIEnv) -> jint { info!("Executing modify_ro_build_date_ymdhm"); report("ro.build.date.YmdHM", build_date::BuildDateReport::current(env)) }
 fn modify_ro_build_description() -> jint { info!("Executing modify_ro_build_description"); 0 }
 fn modify_ro_common_soft() -> jint { info!("Executing modify_ro_common_soft"); 0 }
 fn modify_ro_build_release_type() -> jint { info!("Executing modify_ro_build_release_type"); 0 }
@@ -617,7 +955,7 @@ fn modify_ro_build_version_ota() -> jint { info!("Executing modify_ro_build_vers
 fn modify_ro_build_soft_majorversion() -> jint { info!("Executing modify_ro_build_soft_majorversion"); 0 }
 fn modify_ro_product_brand() -> jint { info!("Executing modify_ro_product_brand"); 0 }
 fn modify_ro_product_manufacturer() -> jint { info!("Executing modify_ro_product_manufacturer"); 0 }
-fn modify_persist_sys_timezone() -> jint { info!("Executing modify_persist_sys_timezone"); 0 }
+fn modify_persist_sys_timezone(env: &JNIEnv) -> jint { info!("Executing modify_persist_sys_timezone"); report("persist.sys.timezone", locale::LocaleReport::current(env)) }
 fn modify_ro_rom_version() -> jint { info!("Executing modify_ro_rom_version"); 0 }
 fn modify_persist_vendor_ssr_enable_ramdumps() -> jint { info!("Executing modify_persist_vendor_ssr_enable_ramdumps"); 0 }
 fn modify_ro_build_stanv_ab() -> jint { info!("Executing modify_ro_build_stanv_ab"); 0 }
\ No newline at end of file
@@ -0,0 +1,58 @@
+/*
+ * Library lifecycle: what JNI_OnLoad sets up, what JNI_OnUnload tears down,
+ * and access to the stored JavaVM from any thread in between.
+ */
+
+use android_logger::Config;
+use jni::errors::{Error, Result};
+use jni::{JNIEnv, JavaVM};
+use log::Level;
+use std::sync::{Arc, RwLock};
+
+use crate::jni_cache;
+
+static VM: RwLock<Option<Arc<JavaVM>>> = RwLock::new(None);
+
+/// Initialises the logger and the JNI cache, then stores `vm`. Safe to call
+/// again after `on_unload`.
+pub fn on_load(vm: JavaVM) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    android_logger::init_once(Config::default().with_min_level(Level::Info).with_tag("ALOG"));
+
+    jni_cache::init(&vm.get_env()?)?;
+    *VM.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(vm));
+    Ok(())
+}
+
+/// Forgets the `JavaVM` and releases the JNI cache. The logger stays: it
+/// cannot be uninstalled, and a reload reuses it.
+pub fn on_unload() {
+    VM.write().unwrap_or_else(|e| e.into_inner()).take();
+    jni_cache::clear();
+}
+
+/// The `JavaVM` stored by `JNI_OnLoad`, `None` before load or after unload.
+pub fn java_vm() -> Option<Arc<JavaVM>> {
+    VM.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Runs `f` with a `JNIEnv` for the current thread, attaching it for the
+/// duration of the call if it is not attached already.
+///
+/// On a thread attached here there is no Java caller to see an exception,
+/// so one left pending by `f` is logged and cleared.
+pub fn with_env<T, F>(f: F) -> Result<T>
+where
+    F: FnOnce(&JNIEnv) -> Result<T>,
+{
+    let vm = java_vm().ok_or(Error::NullPtr("JavaVM, JNI_OnLoad has not run"))?;
+    if let Ok(env) = vm.get_env() {
+        return f(&env);
+    }
+    let env = vm.attach_current_thread()?;
+    let result = f(&env);
+    if env.exception_check()? {
+        env.exception_describe()?;
+        env.exception_clear()?;
+    }
+    result
+}
@@ -0,0 +1,211 @@
+/*
+ * Effective timezone, locale and clock format behind persist.sys.timezone,
+ * persist.sys.tz and ro.product.locale.
+ */
+
+use jni::errors::Result;
+use jni::objects::JObject;
+use jni::JNIEnv;
+use serde::Serialize;
+
+use crate::context::{self, Namespace};
+use crate::props;
+use crate::time;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TimeZoneInfo {
+    /// `TimeZone.getDefault().getID()`, e.g. `Asia/Shanghai`.
+    pub id: String,
+    pub raw_offset_ms: i32,
+    /// The offset right now, including daylight saving time.
+    pub offset_ms: i32,
+    pub daylight_time: bool,
+    /// `persist.sys.timezone`, what Settings writes.
+    pub persist_sys_timezone: Option<String>,
+    /// `persist.sys.tz`, an older key some ROMs still set.
+    pub persist_sys_tz: Option<String>,
+}
+
+/// A source that does not agree with the effective value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Mismatch {
+    pub source: String,
+    pub value: String,
+    pub effective: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LocaleReport {
+    pub timezone: TimeZoneInfo,
+    /// `LocaleList.getDefault()` on API 24+, the default locale alone before.
+    pub locales: Vec<String>,
+    /// `Locale.getDefault().toLanguageTag()`.
+    pub default_locale: String,
+    /// `persist.sys.locale`, what Settings writes.
+    pub persist_sys_locale: Option<String>,
+    /// `ro.product.locale`, the factory default. Not compared: it only
+    /// differs because the user picked another language.
+    pub ro_product_locale: Option<String>,
+    /// `DateFormat.is24HourFormat(context)`.
+    pub hour_24: bool,
+    /// `Settings.System.TIME_12_24`: `"12"`, `"24"` or unset to follow the locale.
+    pub time_12_24: Option<String>,
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl LocaleReport {
+    pub fn current(env: &JNIEnv) -> Result<LocaleReport> {
+        LocaleReport::from_context(env, context::application_context(env)?)
+    }
+
+    pub fn from_context<'a>(env: &JNIEnv<'a>, context: JObject<'a>) -> Result<LocaleReport> {
+        let default_locale = env.call_static_method("java/util/Locale", "getDefault", "()Ljava/util/Locale;", &[])?.l()?;
+        let default_locale = env.call_method(default_locale, "toLanguageTag", "()Ljava/lang/String;", &[])?.l()?;
+        let default_locale = context::optional_string(env, default_locale)?.unwrap_or_default();
+        let locales = if context::sdk_int(env)? >= 24 {
+            let list = env.call_static_method("android/os/LocaleList", "getDefault", "()Landroid/os/LocaleList;", &[])?.l()?;
+            let tags = env.call_method(list, "toLanguageTags", "()Ljava/lang/String;", &[])?.l()?;
+            let tags = context::optional_string(env, tags)?.unwrap_or_default();
+            tags.split(',').filter(|tag| !tag.is_empty()).map(String::from).collect()
+        } else {
+            vec![default_locale.clone()]
+        };
+        let hour_24 = env
+            .call_static_method(
+                "android/text/format/DateFormat",
+                "is24HourFormat",
+                "(Landroid/content/Context;)Z",
+                &[context.into()],
+            )?
+            .z()?;
+        let time_12_24 = context::settings_string(env, context, Namespace::System, "time_12_24")?;
+
+        Ok(LocaleReport::new(
+            time_zone(env)?,
+            locales,
+            default_locale,
+            props::get("persist.sys.locale"),
+            props::get("ro.product.locale"),
+            hour_24,
+            time_12_24,
+        ))
+    }
+
+    pub fn new(
+        timezone: TimeZoneInfo,
+        locales: Vec<String>,
+        default_locale: String,
+        persist_sys_locale: Option<String>,
+        ro_product_locale: Option<String>,
+        hour_24: bool,
+        time_12_24: Option<String>,
+    ) -> LocaleReport {
+        let mut report = LocaleReport {
+            timezone,
+            locales,
+            default_locale,
+            persist_sys_locale,
+            ro_product_locale,
+            hour_24,
+            time_12_24,
+            mismatches: Vec::new(),
+        };
+        report.mismatches = report.find_mismatches();
+        report
+    }
+
+    fn find_mismatches(&self) -> Vec<Mismatch> {
+        let mut mismatches = Vec::new();
+        let mut compare = |source: &str, value: &Option<String>, effective: &str, same: fn(&str, &str) -> bool| {
+            if let Some(value) = value.as_deref().filter(|value| !value.is_empty()) {
+                if !same(value, effective) {
+                    mismatches.push(Mismatch {
+                        source: source.to_string(),
+                        value: value.to_string(),
+                        effective: effective.to_string(),
+                    });
+                }
+            }
+        };
+        let timezone = &self.timezone;
+        compare("persist.sys.timezone", &timezone.persist_sys_timezone, &timezone.id, |a, b| a == b);
+        compare("persist.sys.tz", &timezone.persist_sys_tz, &timezone.id, |a, b| a == b);
+        compare("persist.sys.locale", &self.persist_sys_locale, &self.default_locale, same_locale);
+        let clock = if self.hour_24 { "24" } else { "12" };
+        compare("time_12_24", &self.time_12_24, clock, |a, b| a == b);
+        mismatches
+    }
+}
+
+/// `TimeZone.getDefault()` and the timezone properties.
+pub fn time_zone(env: &JNIEnv) -> Result<TimeZoneInfo> {
+    let zone = env.call_static_method("java/util/TimeZone", "getDefault", "()Ljava/util/TimeZone;", &[])?.l()?;
+    let raw_offset_ms = env.call_method(zone, "getRawOffset", "()I", &[])?.i()?;
+    let offset_ms = env.call_method(zone, "getOffset", "(J)I", &[time::now_ms().into()])?.i()?;
+    let id = env.call_method(zone, "getID", "()Ljava/lang/String;", &[])?.l()?;
+    Ok(TimeZoneInfo {
+        id: context::optional_string(env, id)?.unwrap_or_default(),
+        raw_offset_ms,
+        offset_ms,
+        daylight_time: offset_ms != raw_offset_ms,
+        persist_sys_timezone: props::get("persist.sys.timezone"),
+        persist_sys_tz: props::get("persist.sys.tz"),
+    })
+}
+
+/// Language tags compared case-insensitively, accepting `_` for `-` as in
+/// the legacy `zh_CN` form.
+pub fn same_locale(a: &str, b: &str) -> bool {
+    a.replace('_', "-").eq_ignore_ascii_case(&b.replace('_', "-"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shanghai() -> TimeZoneInfo {
+        TimeZoneInfo {
+            id: "Asia/Shanghai".to_string(),
+            raw_offset_ms: 28_800_000,
+            offset_ms: 28_800_000,
+            daylight_time: false,
+            persist_sys_timezone: Some("Asia/Shanghai".to_string()),
+            persist_sys_tz: None,
+        }
+    }
+
+    #[test]
+    fn agreeing_sources_have_no_mismatches() {
+        let report = LocaleReport::new(
+            shanghai(),
+            vec!["zh-CN".to_string(), "en-US".to_string()],
+            "zh-CN".to_string(),
+            Some("zh_cn".to_string()),
+            Some("en-US".to_string()),
+            true,
+            Some("24".to_string()),
+        );
+        assert_eq!(report.mismatches, Vec::new());
+    }
+
+    #[test]
+    fn flags_each_disagreeing_source() {
+        let mut timezone = shanghai();
+        timezone.persist_sys_tz = Some("GMT+08:00".to_string());
+        let report = LocaleReport::new(
+            timezone,
+            vec!["en-US".to_string()],
+            "en-US".to_string(),
+            Some("zh-CN".to_string()),
+            None,
+            false,
+            Some("24".to_string()),
+        );
+        let sources: Vec<&str> = report.mismatches.iter().map(|mismatch| mismatch.source.as_str()).collect();
+        assert_eq!(sources, ["persist.sys.tz", "persist.sys.locale", "time_12_24"]);
+        assert_eq!(
+            report.mismatches[2],
+            Mismatch { source: "time_12_24".to_string(), value: "24".to_string(), effective: "12".to_string() }
+        );
+    }
+}
@@ -0,0 +1,116 @@
+/*
+ * Last-known location per provider behind GPS位置信息, including whether the
+ * fix came from a mock location provider.
+ */
+
+use jni::errors::{Error, Result};
+use jni::objects::{JObject, JString};
+use jni::JNIEnv;
+use serde::Serialize;
+
+use crate::context;
+use crate::frame;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LocationState {
+    Available,
+    /// The provider has no last-known location.
+    NoFix,
+    /// `getLastKnownLocation` threw `SecurityException`: neither
+    /// `ACCESS_FINE_LOCATION` nor `ACCESS_COARSE_LOCATION` is granted.
+    PermissionDenied,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Fix {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude: Option<f64>,
+    pub accuracy_m: Option<f32>,
+    /// Wall-clock time of the fix in milliseconds since the epoch.
+    pub time_ms: i64,
+    pub elapsed_realtime_nanos: i64,
+    /// `Location.isMock()` on API 31+, `isFromMockProvider()` before.
+    pub is_mock: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProviderLocation {
+    pub provider: String,
+    pub enabled: bool,
+    pub state: LocationState,
+    pub fix: Option<Fix>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LocationReport {
+    pub providers: Vec<ProviderLocation>,
+    /// True when any provider's last fix is flagged as mock.
+    pub any_mock: bool,
+}
+
+impl LocationReport {
+    pub fn current(env: &JNIEnv) -> Result<LocationReport> {
+        LocationReport::from_context(env, context::application_context(env)?)
+    }
+
+    pub fn from_context<'a>(env: &JNIEnv<'a>, context: JObject<'a>) -> Result<LocationReport> {
+        let sdk_int = context::sdk_int(env)?;
+        let manager = context::system_service(env, context, "location")?;
+        let providers = env.call_method(manager, "getAllProviders", "()Ljava/util/List;", &[])?.l()?;
+
+        let providers = frame::map_list(env, providers, |provider| read_provider(env, manager, provider, sdk_int))?;
+        let any_mock = providers.iter().any(|provider| provider.fix.as_ref().is_some_and(|fix| fix.is_mock));
+        Ok(LocationReport { providers, any_mock })
+    }
+}
+
+fn read_provider<'a>(
+    env: &JNIEnv<'a>,
+    manager: JObject<'a>,
+    provider: JObject<'a>,
+    sdk_int: i32,
+) -> Result<ProviderLocation> {
+    let name: String = env.get_string(JString::from(provider))?.into();
+    let enabled = env.call_method(manager, "isProviderEnabled", "(Ljava/lang/String;)Z", &[provider.into()])?.z()?;
+    let location = match env.call_method(
+        manager,
+        "getLastKnownLocation",
+        "(Ljava/lang/String;)Landroid/location/Location;",
+        &[provider.into()],
+    ) {
+        Ok(location) => location.l()?,
+        Err(Error::JavaException) if context::catch_exception(env, "java/lang/SecurityException")? => {
+            return Ok(ProviderLocation { provider: name, enabled, state: LocationState::PermissionDenied, fix: None });
+        }
+        Err(e) => return Err(e),
+    };
+    let fix = if location.is_null() { None } else { Some(read_fix(env, location, sdk_int)?) };
+    Ok(ProviderLocation {
+        provider: name,
+        enabled,
+        state: if fix.is_some() { LocationState::Available } else { LocationState::NoFix },
+        fix,
+    })
+}
+
+fn read_fix<'a>(env: &JNIEnv<'a>, location: JObject<'a>, sdk_int: i32) -> Result<Fix> {
+    let has = |name: &str| -> Result<bool> { env.call_method(location, name, "()Z", &[])?.z() };
+    let is_mock = if sdk_int >= 31 { "isMock" } else { "isFromMockProvider" };
+    Ok(Fix {
+        latitude: env.call_method(location, "getLatitude", "()D", &[])?.d()?,
+        longitude: env.call_method(location, "getLongitude", "()D", &[])?.d()?,
+        altitude: match has("hasAltitude")? {
+            true => Some(env.call_method(location, "getAltitude", "()D", &[])?.d()?),
+            false => None,
+        },
+        accuracy_m: match has("hasAccuracy")? {
+            true => Some(env.call_method(location, "getAccuracy", "()F", &[])?.f()?),
+            false => None,
+        },
+        time_ms: env.call_method(location, "getTime", "()J", &[])?.j()?,
+        elapsed_realtime_nanos: env.call_method(location, "getElapsedRealtimeNanos", "()J", &[])?.j()?,
+        is_mock: has(is_mock)?,
+    })
+}
@@ -0,0 +1,211 @@
+/*
+ * Typed access to `android.content.pm.PackageInfo`, shared by every
+ * package-related handler.
+ */
+
+use jni::errors::Result;
+use jni::objects::{JObject, JString};
+use jni::JNIEnv;
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::context;
+use crate::frame;
+use crate::jni_cache::{self, Field, JniCache};
+
+/// `PackageManager.GET_SIGNATURES`.
+pub const GET_SIGNATURES: i32 = 0x40;
+/// `PackageManager.GET_PERMISSIONS`.
+pub const GET_PERMISSIONS: i32 = 0x1000;
+/// `PackageManager.GET_SIGNING_CERTIFICATES`, API 28+.
+pub const GET_SIGNING_CERTIFICATES: i32 = 0x0800_0000;
+
+/// The flags that make `getPackageInfo` fill in everything the view reads.
+pub fn all_flags(sdk_int: i32) -> i32 {
+    let signing = if sdk_int >= 28 { GET_SIGNING_CERTIFICATES } else { GET_SIGNATURES };
+    signing | GET_PERMISSIONS
+}
+
+/// One signing certificate.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SignatureInfo {
+    /// `Signature.hashCode()`, the value `getAppInfo` returns.
+    pub hash_code: i32,
+    /// The DER certificate, hex encoded.
+    pub certificate: String,
+}
+
+impl SignatureInfo {
+    pub fn from_bytes(bytes: &[u8]) -> SignatureInfo {
+        SignatureInfo { hash_code: java_array_hash_code(bytes), certificate: to_hex(bytes) }
+    }
+}
+
+/// Everything a `PackageInfoView` exposes, read in one go.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PackageSummary {
+    pub package_name: String,
+    pub version_name: Option<String>,
+    pub version_code: i64,
+    pub first_install_time: i64,
+    pub last_update_time: i64,
+    pub target_sdk: Option<i32>,
+    pub requested_permissions: Vec<String>,
+    pub signatures: Vec<SignatureInfo>,
+}
+
+pub struct PackageInfoView<'a, 'b> {
+    env: &'b JNIEnv<'a>,
+    cache: Arc<JniCache>,
+    info: JObject<'a>,
+}
+
+impl<'a, 'b> PackageInfoView<'a, 'b> {
+    pub fn new(env: &'b JNIEnv<'a>, info: JObject<'a>) -> Result<PackageInfoView<'a, 'b>> {
+        Ok(PackageInfoView { env, cache: jni_cache::get(env)?, info })
+    }
+
+    /// `context.getPackageManager().getPackageInfo(context.getPackageName(), flags)`.
+    pub fn own(env: &'b JNIEnv<'a>, context: JObject<'a>, flags: i32) -> Result<PackageInfoView<'a, 'b>> {
+        let cache = jni_cache::get(env)?;
+        let package_manager = cache.get_package_manager.call_object(env, context, &[])?;
+        let package_name = cache.get_package_name.call_object(env, context, &[])?;
+        let info = cache.get_package_info.call_object(env, package_manager, &[package_name.into(), flags.into()])?;
+        PackageInfoView::new(env, info)
+    }
+
+    pub fn object(&self) -> JObject<'a> {
+        self.info
+    }
+
+    pub fn package_name(&self) -> Result<String> {
+        Ok(self.string_field(self.cache.package_name)?.unwrap_or_default())
+    }
+
+    /// The `packageName` field as a Java string, for passing back to `PackageManager`.
+    pub fn package_name_object(&self) -> Result<JObject<'a>> {
+        self.cache.package_name.get_object(self.env, self.info)
+    }
+
+    pub fn version_name(&self) -> Result<Option<String>> {
+        self.string_field(self.cache.version_name)
+    }
+
+    /// `getLongVersionCode()` on API 28+, the `versionCode` int before.
+    pub fn version_code(&self) -> Result<i64> {
+        match self.cache.get_long_version_code {
+            Some(method) => method.call_long(self.env, self.info, &[]),
+            None => Ok(self.cache.version_code.get_int(self.env, self.info)? as i64),
+        }
+    }
+
+    pub fn first_install_time(&self) -> Result<i64> {
+        self.cache.first_install_time.get_long(self.env, self.info)
+    }
+
+    pub fn last_update_time(&self) -> Result<i64> {
+        self.cache.last_update_time.get_long(self.env, self.info)
+    }
+
+    /// `applicationInfo.targetSdkVersion`, `None` when there is no `ApplicationInfo`.
+    pub fn target_sdk(&self) -> Result<Option<i32>> {
+        self.application_int(self.cache.target_sdk_version)
+    }
+
+    /// `applicationInfo.flags`, 0 when there is no `ApplicationInfo`.
+    pub fn application_flags(&self) -> Result<i32> {
+        Ok(self.application_int(self.cache.flags)?.unwrap_or(0))
+    }
+
+    /// Requires `GET_PERMISSIONS`.
+    pub fn requested_permissions(&self) -> Result<Vec<String>> {
+        let array = self.cache.requested_permissions.get_object(self.env, self.info)?;
+        frame::map_array(self.env, array.into_inner(), |element| Ok(self.env.get_string(JString::from(element))?.into()))
+    }
+
+    /// The current signers: `signingInfo.getApkContentsSigners()` on API 28+
+    /// when `GET_SIGNING_CERTIFICATES` was requested, otherwise `signatures`
+    /// (requires `GET_SIGNATURES`).
+    pub fn signatures(&self) -> Result<Vec<SignatureInfo>> {
+        let mut array = JObject::null();
+        if let (Some(signing_info), Some(get_signers)) =
+            (self.cache.signing_info_field, self.cache.get_apk_contents_signers)
+        {
+            let signing_info = signing_info.get_object(self.env, self.info)?;
+            if !signing_info.is_null() {
+                array = get_signers.call_object(self.env, signing_info, &[])?;
+            }
+        }
+        if array.is_null() {
+            array = self.cache.signatures.get_object(self.env, self.info)?;
+        }
+        frame::map_array(self.env, array.into_inner(), |signature| {
+            let bytes = self.cache.to_byte_array.call_object(self.env, signature, &[])?;
+            Ok(SignatureInfo::from_bytes(&self.env.convert_byte_array(bytes.into_inner())?))
+        })
+    }
+
+    pub fn summary(&self) -> Result<PackageSummary> {
+        Ok(PackageSummary {
+            package_name: self.package_name()?,
+            version_name: self.version_name()?,
+            version_code: self.version_code()?,
+            first_install_time: self.first_install_time()?,
+            last_update_time: self.last_update_time()?,
+            target_sdk: self.target_sdk()?,
+            requested_permissions: self.requested_permissions()?,
+            signatures: self.signatures()?,
+        })
+    }
+
+    fn string_field(&self, field: Field) -> Result<Option<String>> {
+        let value = field.get_object(self.env, self.info)?;
+        if value.is_null() {
+            return Ok(None);
+        }
+        Ok(Some(self.env.get_string(JString::from(value))?.into()))
+    }
+
+    fn application_int(&self, field: Field) -> Result<Option<i32>> {
+        let application_info = self.cache.application_info_field.get_object(self.env, self.info)?;
+        if application_info.is_null() {
+            return Ok(None);
+        }
+        Ok(Some(field.get_int(self.env, application_info)?))
+    }
+}
+
+/// The own package's summary, with every flag the view needs.
+pub fn own_summary(env: &JNIEnv) -> Result<PackageSummary> {
+    let context = context::application_context(env)?;
+    PackageInfoView::own(env, context, all_flags(context::sdk_int(env)?))?.summary()
+}
+
+/// `java.util.Arrays.hashCode(byte[])`, which is also what `Signature.hashCode()` returns.
+pub fn java_array_hash_code(bytes: &[u8]) -> i32 {
+    bytes.iter().fold(1i32, |hash, &byte| hash.wrapping_mul(31).wrapping_add(byte as i8 as i32))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_code_matches_java_arrays_hash_code() {
+        // Values from java.util.Arrays.hashCode(new byte[] {...}).
+        assert_eq!(java_array_hash_code(&[]), 1);
+        assert_eq!(java_array_hash_code(&[1, 2, 3]), 30817);
+        assert_eq!(java_array_hash_code(&[0x30, 0x82, 0xff]), 72012);
+        assert_eq!(java_array_hash_code(&[0x7f; 16]), 1069366529);
+    }
+
+    #[test]
+    fn signature_info_from_certificate_bytes() {
+        let info = SignatureInfo::from_bytes(&[0x30, 0x82, 0xff]);
+        assert_eq!(info, SignatureInfo { hash_code: 72012, certificate: "3082ff".to_string() });
+    }
+}
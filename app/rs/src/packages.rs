@@ -0,0 +1,203 @@
+/*
+ * Installed package inventory behind pm list packages, -s and -3.
+ */
+
+use jni::errors::{Error, Result};
+use jni::objects::JObject;
+use jni::JNIEnv;
+use serde::Serialize;
+
+use crate::context;
+use crate::frame;
+use crate::jni_cache;
+use crate::package_info::PackageInfoView;
+use crate::time;
+
+/// `ApplicationInfo.FLAG_SYSTEM`.
+pub const FLAG_SYSTEM: i32 = 1 << 0;
+/// `ApplicationInfo.FLAG_UPDATED_SYSTEM_APP`.
+pub const FLAG_UPDATED_SYSTEM_APP: i32 = 1 << 7;
+
+/// Which packages to list, mirroring the `pm list packages` switches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackageFilter {
+    All,
+    /// `-s`
+    System,
+    /// `-3`
+    ThirdParty,
+}
+
+impl PackageFilter {
+    pub fn matches(&self, package: &Package) -> bool {
+        match self {
+            PackageFilter::All => true,
+            PackageFilter::System => package.system,
+            PackageFilter::ThirdParty => !package.system,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Package {
+    pub package_name: String,
+    pub version_name: Option<String>,
+    /// `getLongVersionCode()` on API 28+, `versionCode` before.
+    pub version_code: i64,
+    pub first_install_time: i64,
+    pub last_update_time: i64,
+    pub installer: Option<String>,
+    pub system: bool,
+    pub updated_system_app: bool,
+}
+
+/// Whether the list can be trusted to be complete.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Visibility {
+    All,
+    /// API 30+ package visibility filtering applies: without
+    /// `QUERY_ALL_PACKAGES` only packages matched by the manifest's
+    /// `<queries>` (plus system packages that are always visible) are returned.
+    Filtered,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PackageList {
+    pub visibility: Visibility,
+    pub packages: Vec<Package>,
+}
+
+impl PackageList {
+    pub fn current(env: &JNIEnv, filter: PackageFilter) -> Result<PackageList> {
+        PackageList::from_context(env, context::application_context(env)?, filter)
+    }
+
+    pub fn from_context<'a>(env: &JNIEnv<'a>, context: JObject<'a>, filter: PackageFilter) -> Result<PackageList> {
+        let sdk_int = context::sdk_int(env)?;
+        let package_manager = jni_cache::get(env)?.get_package_manager.call_object(env, context, &[])?;
+        let installed = env
+            .call_method(package_manager, "getInstalledPackages", "(I)Ljava/util/List;", &[0.into()])?
+            .l()?;
+
+        let packages = frame::map_list(env, installed, |package_info| {
+            read_package(env, package_manager, &PackageInfoView::new(env, package_info)?, sdk_int)
+        })?;
+        let packages = packages.into_iter().filter(|package| filter.matches(package)).collect();
+        Ok(PackageList { visibility: visibility(env, context, sdk_int)?, packages })
+    }
+
+    /// The `package:<name>` lines `pm list packages` prints.
+    pub fn pm_output(&self) -> String {
+        self.packages.iter().map(|package| format!("package:{}\n", package.package_name)).collect()
+    }
+}
+
+/// `PackageInfo.firstInstallTime` and `lastUpdateTime` of the calling app.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InstallTimes {
+    pub first_install_time: i64,
+    /// `first_install_time` in ISO-8601, e.g. `2022-02-15T08:30:00.000Z`.
+    pub first_install: String,
+    pub last_update_time: i64,
+    pub last_update: String,
+    /// Whole days since the first install, for "installed X days ago".
+    pub days_since_install: i64,
+}
+
+impl InstallTimes {
+    pub fn current(env: &JNIEnv) -> Result<InstallTimes> {
+        InstallTimes::from_context(env, context::application_context(env)?)
+    }
+
+    pub fn from_context<'a>(env: &JNIEnv<'a>, context: JObject<'a>) -> Result<InstallTimes> {
+        let package_info = PackageInfoView::own(env, context, 0)?;
+        Ok(InstallTimes::new(package_info.first_install_time()?, package_info.last_update_time()?, time::now_ms()))
+    }
+
+    pub fn new(first_install_time: i64, last_update_time: i64, now_ms: i64) -> InstallTimes {
+        InstallTimes {
+            first_install_time,
+            first_install: time::format_iso8601(first_install_time),
+            last_update_time,
+            last_update: time::format_iso8601(last_update_time),
+            days_since_install: (now_ms - first_install_time).div_euclid(86_400_000),
+        }
+    }
+}
+
+fn read_package<'a>(
+    env: &JNIEnv<'a>,
+    package_manager: JObject<'a>,
+    package_info: &PackageInfoView<'a, '_>,
+    sdk_int: i32,
+) -> Result<Package> {
+    let flags = package_info.application_flags()?;
+    Ok(Package {
+        package_name: package_info.package_name()?,
+        version_name: package_info.version_name()?,
+        version_code: package_info.version_code()?,
+        first_install_time: package_info.first_install_time()?,
+        last_update_time: package_info.last_update_time()?,
+        installer: installer(env, package_manager, package_info.package_name_object()?, sdk_int)?,
+        system: flags & FLAG_SYSTEM != 0,
+        updated_system_app: flags & FLAG_UPDATED_SYSTEM_APP != 0,
+    })
+}
+
+/// The installing package, via `getInstallSourceInfo` on API 30+ where
+/// `getInstallerPackageName` is deprecated.
+fn installer<'a>(
+    env: &JNIEnv<'a>,
+    package_manager: JObject<'a>,
+    package_name: JObject<'a>,
+    sdk_int: i32,
+) -> Result<Option<String>> {
+    if sdk_int < 30 {
+        let installer = env
+            .call_method(
+                package_manager,
+                "getInstallerPackageName",
+                "(Ljava/lang/String;)Ljava/lang/String;",
+                &[package_name.into()],
+            )?
+            .l()?;
+        return context::optional_string(env, installer);
+    }
+    let source_info = match env.call_method(
+        package_manager,
+        "getInstallSourceInfo",
+        "(Ljava/lang/String;)Landroid/content/pm/InstallSourceInfo;",
+        &[package_name.into()],
+    ) {
+        Ok(source_info) => source_info.l()?,
+        Err(Error::JavaException)
+            if context::catch_exception(env, "android/content/pm/PackageManager$NameNotFoundException")? =>
+        {
+            return Ok(None)
+        }
+        Err(e) => return Err(e),
+    };
+    let installer = env
+        .call_method(source_info, "getInstallingPackageName", "()Ljava/lang/String;", &[])?
+        .l()?;
+    context::optional_string(env, installer)
+}
+
+fn visibility<'a>(env: &JNIEnv<'a>, context: JObject<'a>, sdk_int: i32) -> Result<Visibility> {
+    if sdk_int < 30 {
+        return Ok(Visibility::All);
+    }
+    let application_info = env
+        .call_method(context, "getApplicationInfo", "()Landroid/content/pm/ApplicationInfo;", &[])?
+        .l()?;
+    if env.get_field(application_info, "targetSdkVersion", "I")?.i()? < 30 {
+        return Ok(Visibility::All);
+    }
+    let permission = env.new_string("android.permission.QUERY_ALL_PACKAGES")?;
+    let granted = env
+        .call_method(context, "checkSelfPermission", "(Ljava/lang/String;)I", &[permission.into()])?
+        .i()?
+        == 0;
+    Ok(if granted { Visibility::All } else { Visibility::Filtered })
+}
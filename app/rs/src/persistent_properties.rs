@@ -0,0 +1,267 @@
+/*
+ * persist.* properties as init stores them under /data/property, read from a
+ * rooted device's backup: one `persistent_properties` protobuf since Android
+ * 9, one file per property, named after it, before that.
+ */
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+
+use crate::invalid_data;
+use crate::prop_rules;
+use crate::registry;
+
+pub const PERSISTENT_PROPERTIES: &str = "persistent_properties";
+pub const PREFIX: &str = "persist.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Layout {
+    /// `/data/property/persistent_properties`, Android 9+.
+    Protobuf,
+    /// `/data/property/persist.*`, one file per property.
+    Files,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PersistentProperties {
+    pub layout: Layout,
+    pub properties: BTreeMap<String, String>,
+    /// Records init would not load: names outside `persist.` or breaking the
+    /// property rules, with the reason.
+    pub rejected: Vec<String>,
+}
+
+impl PersistentProperties {
+    /// Reads `path`, a copy of /data/property or of the protobuf file itself.
+    /// The protobuf wins when a directory has both, as init migrates the
+    /// files into it.
+    pub fn load(path: &Path) -> io::Result<PersistentProperties> {
+        if path.is_file() {
+            return PersistentProperties::parse(&std::fs::read(path)?);
+        }
+        let protobuf = path.join(PERSISTENT_PROPERTIES);
+        if protobuf.is_file() {
+            return PersistentProperties::parse(&std::fs::read(protobuf)?);
+        }
+        PersistentProperties::read_files(path)
+    }
+
+    /// The Android 9+ protobuf.
+    pub fn parse(bytes: &[u8]) -> io::Result<PersistentProperties> {
+        Ok(PersistentProperties::new(Layout::Protobuf, decode(bytes)?))
+    }
+
+    /// The legacy layout. Like init, only regular files named `persist.*`
+    /// count; anything else in the directory is ignored.
+    pub fn read_files(dir: &Path) -> io::Result<PersistentProperties> {
+        let mut records = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.starts_with(PREFIX) && entry.file_type()?.is_file() {
+                let value = std::fs::read(entry.path())?;
+                records.push((name, String::from_utf8_lossy(&value).into_owned()));
+            }
+        }
+        Ok(PersistentProperties::new(Layout::Files, records))
+    }
+
+    pub fn new(layout: Layout, records: Vec<(String, String)>) -> PersistentProperties {
+        let mut properties = BTreeMap::new();
+        let mut rejected = Vec::new();
+        for (name, value) in records {
+            if !name.starts_with(PREFIX) {
+                rejected.push(format!("{} is not a persist. property", name));
+                continue;
+            }
+            match prop_rules::check_name(&name).and_then(|_| prop_rules::check_value(&name, &value)) {
+                Ok(()) => {
+                    properties.insert(name, value);
+                }
+                Err(e) => rejected.push(e.to_string()),
+            }
+        }
+        PersistentProperties { layout, properties, rejected }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.properties.get(name).map(String::as_str)
+    }
+
+    /// Every `persist.` registry key with its stored value, `None` when the
+    /// backup does not have it.
+    pub fn registry_values(&self) -> BTreeMap<&'static str, Option<&str>> {
+        registry::KEYS.iter().filter(|key| key.starts_with(PREFIX)).map(|key| (*key, self.get(key))).collect()
+    }
+}
+
+/// Decodes `PersistentProperties { repeated PersistentPropertyRecord
+/// properties = 1; }` with `PersistentPropertyRecord { optional string name
+/// = 1; optional string value = 2; }`. Unknown fields are skipped.
+pub fn decode(bytes: &[u8]) -> io::Result<Vec<(String, String)>> {
+    let mut records = Vec::new();
+    let mut message = Message { bytes };
+    while let Some((field, value)) = message.next_field()? {
+        if let (1, Value::Bytes(record)) = (field, value) {
+            let mut record = Message { bytes: record };
+            let (mut name, mut value) = (String::new(), String::new());
+            while let Some(field) = record.next_field()? {
+                match field {
+                    (1, Value::Bytes(bytes)) => name = utf8(bytes, "name")?,
+                    (2, Value::Bytes(bytes)) => value = utf8(bytes, "value")?,
+                    _ => {}
+                }
+            }
+            records.push((name, value));
+        }
+    }
+    Ok(records)
+}
+
+enum Value<'a> {
+    Varint,
+    Bytes(&'a [u8]),
+    Fixed,
+}
+
+/// The fields of one protobuf message, in wire order.
+struct Message<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Message<'a> {
+    fn next_field(&mut self) -> io::Result<Option<(u64, Value<'a>)>> {
+        if self.bytes.is_empty() {
+            return Ok(None);
+        }
+        let key = self.varint()?;
+        let value = match key & 7 {
+            0 => {
+                self.varint()?;
+                Value::Varint
+            }
+            1 => {
+                self.take(8)?;
+                Value::Fixed
+            }
+            2 => {
+                let len = self.varint()?;
+                Value::Bytes(self.take(usize::try_from(len).unwrap_or(usize::MAX))?)
+            }
+            5 => {
+                self.take(4)?;
+                Value::Fixed
+            }
+            wire_type => return Err(invalid_data(format!("unsupported protobuf wire type {}", wire_type))),
+        };
+        Ok(Some((key >> 3, value)))
+    }
+
+    fn varint(&mut self) -> io::Result<u64> {
+        let mut value = 0u64;
+        for (i, byte) in self.bytes.iter().enumerate().take(10) {
+            value |= u64::from(byte & 0x7f) << (7 * i);
+            if byte & 0x80 == 0 {
+                self.bytes = &self.bytes[i + 1..];
+                return Ok(value);
+            }
+        }
+        Err(invalid_data("truncated or overlong protobuf varint".to_string()))
+    }
+
+    fn take(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        if len > self.bytes.len() {
+            return Err(invalid_data(format!("protobuf field of {} bytes, {} left", len, self.bytes.len())));
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+}
+
+fn utf8(bytes: &[u8], what: &str) -> io::Result<String> {
+    String::from_utf8(bytes.to_vec()).map_err(|_| invalid_data(format!("record {} is not UTF-8", what)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+    /// A length-delimited field.
+    fn field(number: u8, payload: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![number << 3 | 2, payload.len() as u8];
+        bytes.extend_from_slice(payload);
+        bytes
+    }
+
+    fn record(name: &str, value: &str) -> Vec<u8> {
+        field(1, &[field(1, name.as_bytes()), field(2, value.as_bytes())].concat())
+    }
+
+    #[test]
+    fn reads_the_protobuf() {
+        let properties = PersistentProperties::load(&Path::new(FIXTURES).join("data_property")).unwrap();
+        assert_eq!(properties.layout, Layout::Protobuf);
+        assert_eq!(properties.properties.len(), 7);
+        assert_eq!(properties.get("persist.sys.timezone"), Some("Asia/Shanghai"));
+        assert_eq!(properties.get("persist.data.df.dl_mode"), Some("5"));
+        assert_eq!(properties.get("persist.vendor.camera.privapp.list").unwrap().split(',').count(), 2);
+        assert!(properties.rejected.is_empty());
+    }
+
+    #[test]
+    fn reads_the_legacy_files() {
+        let properties = PersistentProperties::load(&Path::new(FIXTURES).join("data_property_legacy")).unwrap();
+        assert_eq!(properties.layout, Layout::Files);
+        assert_eq!(
+            properties.properties.keys().collect::<Vec<_>>(),
+            ["persist.data.df.mux_count", "persist.rmnet.data.enable", "persist.sys.timezone", "persist.sys.usb.config"]
+        );
+        assert_eq!(properties.get("persist.sys.timezone"), Some("America/Los_Angeles"));
+    }
+
+    #[test]
+    fn maps_onto_registry_keys() {
+        let properties = PersistentProperties::load(&Path::new(FIXTURES).join("data_property")).unwrap();
+        let values = properties.registry_values();
+        assert!(values.keys().all(|key| key.starts_with(PREFIX)));
+        assert_eq!(values["persist.sys.timezone"], Some("Asia/Shanghai"));
+        assert_eq!(values["persist.data.df.agg.dl_pkt"], Some("10"));
+        assert_eq!(values["persist.sys.tz"], None);
+        // Not a registry key.
+        assert!(!values.contains_key("persist.sys.locale"));
+    }
+
+    #[test]
+    fn skips_unknown_fields_and_rejects_what_init_would() {
+        let bytes = [
+            vec![0x10, 0x96, 0x01],
+            record("persist.sys.tz", "UTC"),
+            field(3, b"ignored"),
+            record("ro.build.id", "QKQ1"),
+            record("persist..x", "1"),
+            record("persist.long", &"x".repeat(100)),
+        ]
+        .concat();
+        let properties = PersistentProperties::parse(&bytes).unwrap();
+        assert_eq!(properties.properties.len(), 1);
+        assert_eq!(properties.get("persist.sys.tz"), Some("UTC"));
+        assert_eq!(properties.rejected.len(), 3);
+        assert_eq!(properties.rejected[0], "ro.build.id is not a persist. property");
+    }
+
+    #[test]
+    fn rejects_malformed_protobuf() {
+        let record = record("persist.sys.tz", "UTC");
+        assert!(decode(&record[..record.len() - 1]).is_err());
+        assert!(decode(&[0x0a, 0x80]).is_err());
+        assert_eq!(decode(&[0x0b]).unwrap_err().to_string(), "unsupported protobuf wire type 3");
+        assert!(decode(&field(1, &field(1, &[0xff, 0xfe]))).is_err());
+        assert_eq!(decode(&[]).unwrap(), []);
+    }
+}
@@ -0,0 +1,263 @@
+/*
+ * bionic's prop area files, the shared memory behind system properties, read
+ * from a copy of /dev/__properties__ for offline analysis. Since Android 8
+ * there is one file per SELinux context, named after it; before that a
+ * single /dev/__properties__ file held every property.
+ */
+
+use serde::Serialize;
+use std::collections::HashSet;
+use std::io;
+use std::path::Path;
+
+use crate::invalid_data;
+use crate::property_info::PropertyInfo;
+
+/// `PROP_AREA_MAGIC`, "PROP".
+pub const MAGIC: u32 = 0x504f_5250;
+/// `PROP_AREA_VERSION`, in use since Android 4.2.
+pub const VERSION: u32 = 0xfc6e_d0ab;
+/// The trie over context names, not a prop area.
+pub const PROPERTY_INFO: &str = "property_info";
+/// The area that only carries the global change serial.
+pub const PROPERTIES_SERIAL: &str = "properties_serial";
+
+/// `bytes_used`, `serial`, `magic`, `version` and 28 reserved words.
+const HEADER_SIZE: usize = 128;
+/// `prop_info`: the serial, then a `PROP_VALUE_MAX` value.
+const PROP_INFO_NAME: u32 = 96;
+/// Where a long `prop_info` keeps its value's offset, after a 56-byte error message.
+const LONG_VALUE_OFFSET: u32 = 60;
+/// Set in the serial of values too long for the inline buffer (Android 8+).
+const LONG_FLAG: u32 = 1 << 16;
+
+/// One property from a prop area.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Property {
+    pub name: String,
+    pub value: String,
+    /// The area's file name, `None` for the single pre-Android 8 area.
+    pub context: Option<String>,
+    /// From property_info, Android 10+.
+    pub value_type: Option<String>,
+}
+
+/// Every property in one prop area file, in trie order.
+pub fn parse(bytes: &[u8]) -> io::Result<Vec<(String, String)>> {
+    if bytes.len() < HEADER_SIZE {
+        return Err(invalid_data(format!("prop area is {} bytes, shorter than its header", bytes.len())));
+    }
+    let header = |index: usize| {
+        let word = &bytes[index * 4..index * 4 + 4];
+        u32::from_le_bytes([word[0], word[1], word[2], word[3]])
+    };
+    if header(2) != MAGIC {
+        return Err(invalid_data(format!("bad prop area magic {:#010x}", header(2))));
+    }
+    if header(3) != VERSION {
+        return Err(invalid_data(format!("unsupported prop area version {:#010x}", header(3))));
+    }
+    let used = (header(0) as usize).min(bytes.len() - HEADER_SIZE);
+    let area = Area { data: &bytes[HEADER_SIZE..HEADER_SIZE + used] };
+
+    let mut properties = Vec::new();
+    let mut visited = HashSet::new();
+    // The root `prop_bt` sits at offset 0 with an empty name. Each `prop_bt`
+    // holds its name length, then the offsets of its prop_info and of its
+    // left sibling, right sibling and children.
+    let mut pending = vec![0];
+    while let Some(node) = pending.pop() {
+        if !visited.insert(node) {
+            return Err(invalid_data(format!("prop area trie revisits offset {}", node)));
+        }
+        let info = area.u32(node, 4)?;
+        if info != 0 {
+            properties.push(area.prop_info(info)?);
+        }
+        // Right, children, then left, so the left sibling is visited first.
+        for field in [12, 16, 8] {
+            let next = area.u32(node, field)?;
+            if next != 0 {
+                pending.push(next);
+            }
+        }
+    }
+    Ok(properties)
+}
+
+/// Every property in a copied `/dev/__properties__`, sorted by name. `path`
+/// is the directory, or the single area file of releases before Android 8.
+pub fn dump(path: &Path) -> io::Result<Vec<Property>> {
+    if path.is_file() {
+        let mut properties: Vec<Property> = parse(&std::fs::read(path)?)?
+            .into_iter()
+            .map(|(name, value)| Property { name, value, context: None, value_type: None })
+            .collect();
+        properties.sort_by(|a, b| a.name.cmp(&b.name));
+        return Ok(properties);
+    }
+    let info_path = path.join(PROPERTY_INFO);
+    let info = if info_path.is_file() { Some(PropertyInfo::read(&info_path)?) } else { None };
+    let mut properties = Vec::new();
+    for entry in std::fs::read_dir(path)? {
+        let entry = entry?;
+        let context = entry.file_name().to_string_lossy().into_owned();
+        if context == PROPERTY_INFO || context == PROPERTIES_SERIAL || !entry.file_type()?.is_file() {
+            continue;
+        }
+        let area = parse(&std::fs::read(entry.path())?)
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", context, e)))?;
+        for (name, value) in area {
+            let value_type = info.as_ref().and_then(|info| info.lookup(&name).1).map(String::from);
+            properties.push(Property { name, value, context: Some(context.clone()), value_type });
+        }
+    }
+    properties.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(properties)
+}
+
+/// Bounds-checked reads at offsets from the start of the area's data.
+struct Area<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Area<'a> {
+    fn u32(&self, base: u32, delta: u32) -> io::Result<u32> {
+        let bytes = self.slice(base, delta, 4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn slice(&self, base: u32, delta: u32, len: usize) -> io::Result<&'a [u8]> {
+        let start = self.offset(base, delta)?;
+        self.data
+            .get(start..start.saturating_add(len))
+            .ok_or_else(|| invalid_data(format!("{} bytes at {}+{} run past the prop area", len, base, delta)))
+    }
+
+    fn c_string(&self, base: u32, delta: u32) -> io::Result<String> {
+        let tail = &self.data[self.offset(base, delta)?..];
+        let len = tail
+            .iter()
+            .position(|b| *b == 0)
+            .ok_or_else(|| invalid_data(format!("unterminated string at offset {}+{}", base, delta)))?;
+        Ok(String::from_utf8_lossy(&tail[..len]).into_owned())
+    }
+
+    fn offset(&self, base: u32, delta: u32) -> io::Result<usize> {
+        match base.checked_add(delta) {
+            Some(offset) if (offset as usize) <= self.data.len() => Ok(offset as usize),
+            _ => Err(invalid_data(format!("offset {}+{} is past the end of the prop area", base, delta))),
+        }
+    }
+
+    /// A `prop_info`: the serial, whose top byte is the value length, the
+    /// inline value or, for long values, an offset from the `prop_info` to
+    /// the value, then the full name.
+    fn prop_info(&self, offset: u32) -> io::Result<(String, String)> {
+        let serial = self.u32(offset, 0)?;
+        let name = self.c_string(offset, PROP_INFO_NAME)?;
+        let value = if serial & LONG_FLAG != 0 {
+            self.c_string(offset, self.u32(offset, LONG_VALUE_OFFSET)?)?
+        } else {
+            String::from_utf8_lossy(self.slice(offset, 4, (serial >> 24) as usize)?).into_owned()
+        };
+        Ok((name, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/dev_properties");
+
+    /// The fixtures with each area renamed to its full context, as on a
+    /// device. The checked-in names have no `:` so Windows can check them out.
+    fn device_copy(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("modify_mac_{}_{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for entry in fs::read_dir(FIXTURES).unwrap() {
+            let file_name = entry.unwrap().file_name().into_string().unwrap();
+            let target = match file_name.as_str() {
+                PROPERTY_INFO | PROPERTIES_SERIAL => file_name.clone(),
+                selinux_type => format!("u:object_r:{}:s0", selinux_type),
+            };
+            fs::copy(Path::new(FIXTURES).join(&file_name), dir.join(target)).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn parses_short_and_long_values() {
+        let properties = parse(include_bytes!("../tests/fixtures/dev_properties/build_prop")).unwrap();
+        let value = |name: &str| properties.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str());
+        assert_eq!(properties.len(), 4);
+        assert_eq!(value("ro.build.version.sdk"), Some("30"));
+        assert_eq!(value("ro.build.id"), Some("RP1A.200720.011"));
+        let fingerprint = value("ro.build.fingerprint").unwrap();
+        assert!(fingerprint.len() > 91 && fingerprint.starts_with("OnePlus/") && fingerprint.ends_with("-x"));
+    }
+
+    #[test]
+    fn empty_areas_and_empty_values() {
+        assert!(parse(include_bytes!("../tests/fixtures/dev_properties/default_prop")).unwrap().is_empty());
+        let system = parse(include_bytes!("../tests/fixtures/dev_properties/system_prop")).unwrap();
+        assert!(system.contains(&("net.hostname".to_string(), String::new())));
+    }
+
+    #[test]
+    fn rejects_foreign_and_truncated_files() {
+        let bytes = include_bytes!("../tests/fixtures/dev_properties/build_prop");
+        assert!(parse(&bytes[..64]).is_err());
+        let mut bad_magic = bytes.to_vec();
+        bad_magic[8] = 0;
+        assert_eq!(parse(&bad_magic).unwrap_err().to_string(), "bad prop area magic 0x504f5200");
+        // bytes_used past the end is clamped; offsets into the missing tail are errors.
+        assert!(parse(&bytes[..HEADER_SIZE + 200]).is_err());
+        assert!(parse(include_bytes!("../tests/fixtures/dev_properties/property_info")).is_err());
+    }
+
+    #[test]
+    fn dumps_a_copied_directory() {
+        let dir = device_copy("dump");
+        let properties = dump(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let names: Vec<&str> = properties.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "net.hostname",
+                "persist.sys.locale",
+                "persist.sys.timezone",
+                "ro.build.fingerprint",
+                "ro.build.id",
+                "ro.build.version.release",
+                "ro.build.version.sdk",
+                "ro.serialno",
+            ]
+        );
+        let sdk = properties.iter().find(|p| p.name == "ro.build.version.sdk").unwrap();
+        assert_eq!(sdk.context.as_deref(), Some("u:object_r:build_prop:s0"));
+        assert_eq!(sdk.value_type.as_deref(), Some("int"));
+        let serial = properties.iter().find(|p| p.name == "ro.serialno").unwrap();
+        assert_eq!(serial.value, "8A3X1B2C");
+        assert_eq!(serial.context.as_deref(), Some("u:object_r:serialno_prop:s0"));
+    }
+
+    #[test]
+    fn dumps_a_single_legacy_area() {
+        let properties = dump(&Path::new(FIXTURES).join("timezone_prop")).unwrap();
+        assert_eq!(
+            properties,
+            [Property {
+                name: "persist.sys.timezone".into(),
+                value: "Asia/Shanghai".into(),
+                context: None,
+                value_type: None,
+            }]
+        );
+    }
+}
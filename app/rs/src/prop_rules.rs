@@ -0,0 +1,218 @@
+/*
+ * The rules init's property service enforces on setprop: legal names, the
+ * PROP_VALUE_MAX limit, write-once ro. properties and on-disk persist. ones.
+ */
+
+use serde::Serialize;
+use std::error::Error;
+use std::fmt;
+
+/// `PROP_VALUE_MAX` from `<sys/system_properties.h>`, including the
+/// terminating NUL: values of non-`ro.` properties hold at most 91 bytes.
+pub const PROP_VALUE_MAX: usize = 92;
+
+/// How the property service treats a property, from its name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PropKind {
+    /// `ro.`: set once, then read-only until reboot. No value length limit.
+    pub read_only: bool,
+    /// `persist.`: also written under /data/property and restored at boot.
+    pub persistent: bool,
+    /// `ctl.`: a request to init to start or stop a service, never stored.
+    pub control: bool,
+}
+
+impl PropKind {
+    pub fn of(name: &str) -> PropKind {
+        PropKind {
+            read_only: name.starts_with("ro."),
+            persistent: name.starts_with("persist."),
+            control: name.starts_with("ctl."),
+        }
+    }
+
+    /// The longest value in bytes, `None` when unlimited.
+    pub fn max_value_len(&self) -> Option<usize> {
+        if self.read_only {
+            None
+        } else {
+            Some(PROP_VALUE_MAX - 1)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PropError {
+    EmptyName,
+    /// The name starts or ends with `.`, or has `..` at this byte offset.
+    EmptySegment {
+        name: String,
+        offset: usize,
+    },
+    /// Only ASCII letters, digits and `.`, `_`, `-`, `@`, `:` are allowed.
+    IllegalChar {
+        name: String,
+        c: char,
+        offset: usize,
+    },
+    ValueTooLong {
+        name: String,
+        len: usize,
+        max: usize,
+    },
+    /// The value contains NUL, which would cut it short.
+    NulInValue {
+        name: String,
+        offset: usize,
+    },
+    /// An `ro.` property that already has a value.
+    ReadOnly {
+        name: String,
+        current: String,
+    },
+}
+
+impl fmt::Display for PropError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PropError::EmptyName => write!(f, "property name is empty"),
+            PropError::EmptySegment { name, offset } => {
+                write!(f, "property name {:?} has an empty segment at byte {}", name, offset)
+            }
+            PropError::IllegalChar { name, c, offset } => write!(
+                f,
+                "property name {:?} has illegal character {:?} at byte {}; only [A-Za-z0-9._-@:] are allowed",
+                name, c, offset
+            ),
+            PropError::ValueTooLong { name, len, max } => write!(
+                f,
+                "value of {} is {} bytes; properties outside ro. hold at most {} (PROP_VALUE_MAX {})",
+                name, len, max, PROP_VALUE_MAX
+            ),
+            PropError::NulInValue { name, offset } => write!(f, "value of {} has NUL at byte {}", name, offset),
+            PropError::ReadOnly { name, current } => {
+                write!(f, "{} is read-only and already set to {:?}", name, current)
+            }
+        }
+    }
+}
+
+impl Error for PropError {}
+
+/// bionic's `IsLegalPropertyName`.
+pub fn check_name(name: &str) -> Result<(), PropError> {
+    if name.is_empty() {
+        return Err(PropError::EmptyName);
+    }
+    let mut previous = '.';
+    for (offset, c) in name.char_indices() {
+        if c == '.' && previous == '.' {
+            return Err(PropError::EmptySegment { name: name.to_string(), offset });
+        }
+        if !(c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '@' | ':')) {
+            return Err(PropError::IllegalChar { name: name.to_string(), c, offset });
+        }
+        previous = c;
+    }
+    if previous == '.' {
+        return Err(PropError::EmptySegment { name: name.to_string(), offset: name.len() });
+    }
+    Ok(())
+}
+
+/// The value limits for `name`, which must already be a legal name.
+pub fn check_value(name: &str, value: &str) -> Result<(), PropError> {
+    if let Some(offset) = value.find('\0') {
+        return Err(PropError::NulInValue { name: name.to_string(), offset });
+    }
+    match PropKind::of(name).max_value_len() {
+        Some(max) if value.len() > max => {
+            Err(PropError::ValueTooLong { name: name.to_string(), len: value.len(), max })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Everything the property service checks before `setprop name value`,
+/// given the property's `current` value.
+pub fn check_set(name: &str, value: &str, current: Option<&str>) -> Result<PropKind, PropError> {
+    check_name(name)?;
+    check_value(name, value)?;
+    let kind = PropKind::of(name);
+    match current {
+        Some(current) if kind.read_only => {
+            Err(PropError::ReadOnly { name: name.to_string(), current: current.to_string() })
+        }
+        _ => Ok(kind),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legal_names() {
+        for name in ["ro.build.version.sdk", "persist.vendor.radio.atfwd.start", "vendor.camera.aux.packagelist"] {
+            assert_eq!(check_name(name), Ok(()));
+        }
+        assert_eq!(check_name("ro.boot.hardware.sku"), Ok(()));
+        assert_eq!(check_name("init.svc.vendor.qti-hardware@1.0:x"), Ok(()));
+        assert_eq!(check_name("DEVICE_PROVISIONED"), Ok(()));
+    }
+
+    #[test]
+    fn illegal_names_report_where() {
+        assert_eq!(check_name(""), Err(PropError::EmptyName));
+        assert_eq!(check_name(".ro.x"), Err(PropError::EmptySegment { name: ".ro.x".into(), offset: 0 }));
+        assert_eq!(check_name("ro..x"), Err(PropError::EmptySegment { name: "ro..x".into(), offset: 3 }));
+        assert_eq!(check_name("ro.x."), Err(PropError::EmptySegment { name: "ro.x.".into(), offset: 5 }));
+        assert_eq!(check_name("ro.x y"), Err(PropError::IllegalChar { name: "ro.x y".into(), c: ' ', offset: 4 }));
+        assert_eq!(
+            check_name("Build.getRadioVersion()"),
+            Err(PropError::IllegalChar { name: "Build.getRadioVersion()".into(), c: '(', offset: 21 })
+        );
+        assert_eq!(check_name("序列号"), Err(PropError::IllegalChar { name: "序列号".into(), c: '序', offset: 0 }));
+    }
+
+    #[test]
+    fn value_length_limit_applies_outside_ro() {
+        let longest = "x".repeat(PROP_VALUE_MAX - 1);
+        let too_long = "x".repeat(PROP_VALUE_MAX);
+        assert_eq!(check_value("persist.sys.tz", &longest), Ok(()));
+        assert_eq!(
+            check_value("persist.sys.tz", &too_long),
+            Err(PropError::ValueTooLong { name: "persist.sys.tz".into(), len: 92, max: 91 })
+        );
+        assert_eq!(check_value("ro.build.fingerprint", &"x".repeat(200)), Ok(()));
+        // Bytes, not characters.
+        assert!(check_value("net.bt.name", &"蓝".repeat(31)).is_err());
+        assert_eq!(
+            check_value("net.bt.name", "a\0b"),
+            Err(PropError::NulInValue { name: "net.bt.name".into(), offset: 1 })
+        );
+    }
+
+    #[test]
+    fn ro_properties_are_write_once() {
+        assert_eq!(check_set("ro.build.id", "QKQ1", None).map(|kind| kind.read_only), Ok(true));
+        assert_eq!(
+            check_set("ro.build.id", "QKQ1", Some("QKQ1")),
+            Err(PropError::ReadOnly { name: "ro.build.id".into(), current: "QKQ1".into() })
+        );
+        assert_eq!(check_set("persist.sys.tz", "UTC", Some("Asia/Shanghai")).map(|kind| kind.persistent), Ok(true));
+        assert_eq!(check_set("ctl.start", "adbd", None).map(|kind| kind.control), Ok(true));
+        assert!(check_set("ro..id", "x", None).is_err());
+    }
+
+    #[test]
+    fn messages_name_the_rule() {
+        let error = check_set("persist.sys.tz", &"x".repeat(100), None).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "value of persist.sys.tz is 100 bytes; properties outside ro. hold at most 91 (PROP_VALUE_MAX 92)"
+        );
+        let error = check_set("ro.serialno", "1", Some("abc")).unwrap_err();
+        assert_eq!(error.to_string(), "ro.serialno is read-only and already set to \"abc\"");
+    }
+}
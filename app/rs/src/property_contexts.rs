@@ -0,0 +1,364 @@
+/*
+ * SELinux property_contexts: which context labels each system property, and
+ * whether an untrusted app may read it. Since Android 8 a denied read does
+ * not fail, __system_property_get just reports the property as unset.
+ */
+
+use serde::Serialize;
+use std::io;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::identifiers::IdState;
+use crate::invalid_data;
+use crate::props;
+use crate::registry;
+
+/// The partition a property_contexts file comes from, in the order init
+/// loads them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Partition {
+    Plat,
+    SystemExt,
+    Product,
+    Vendor,
+    Odm,
+}
+
+impl Partition {
+    pub const ALL: [Partition; 5] =
+        [Partition::Plat, Partition::SystemExt, Partition::Product, Partition::Vendor, Partition::Odm];
+
+    /// Where the partition's file lives, relative to the root, newest layout
+    /// first: Android 8.0 used `nonplat_` for vendor and kept both at the
+    /// root, and before 8.0 there was a single `/property_contexts`.
+    pub fn paths(self) -> &'static [&'static str] {
+        match self {
+            Partition::Plat => {
+                &["system/etc/selinux/plat_property_contexts", "plat_property_contexts", "property_contexts"]
+            }
+            Partition::SystemExt => &["system_ext/etc/selinux/system_ext_property_contexts"],
+            Partition::Product => &["product/etc/selinux/product_property_contexts"],
+            Partition::Vendor => &[
+                "vendor/etc/selinux/vendor_property_contexts",
+                "vendor/etc/selinux/nonplat_property_contexts",
+                "nonplat_property_contexts",
+            ],
+            Partition::Odm => &["odm/etc/selinux/odm_property_contexts"],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchType {
+    /// The entry labels every property whose name starts with it.
+    Prefix,
+    Exact,
+}
+
+/// One `name context [exact|prefix] [type]` line.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Entry {
+    /// The name or prefix; `*` is the default for unmatched properties.
+    pub name: String,
+    /// e.g. `u:object_r:build_prop:s0`.
+    pub context: String,
+    pub match_type: MatchType,
+    /// The declared value type (`string`, `int`, `enum a b`, ...), Android 10+.
+    pub value_type: Option<String>,
+    pub partition: Partition,
+}
+
+impl Entry {
+    /// The SELinux type, the third field of the context.
+    pub fn selinux_type(&self) -> &str {
+        self.context.split(':').nth(2).unwrap_or("")
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        match self.match_type {
+            MatchType::Exact => self.name == name,
+            MatchType::Prefix => name.starts_with(&self.name),
+        }
+    }
+}
+
+/// Property types AOSP's `domain.te` lets every domain read on Android 11+,
+/// plus the ones granted to apps. Other types are denied to untrusted apps;
+/// older releases and vendor policies may allow more.
+pub const APP_READABLE: &[&str] = &[
+    "aaudio_config_prop",
+    "bluetooth_config_prop",
+    "bootloader_prop",
+    "build_odm_prop",
+    "build_prop",
+    "build_vendor_prop",
+    "dalvik_config_prop",
+    "dalvik_runtime_prop",
+    "debug_prop",
+    "exported_config_prop",
+    "exported_default_prop",
+    "exported_secure_prop",
+    "exported_system_prop",
+    "fingerprint_prop",
+    "hal_instrumentation_prop",
+    "hwservicemanager_prop",
+    "init_service_status_prop",
+    "libc_debug_prop",
+    "locale_prop",
+    "logd_prop",
+    "mediadrm_config_prop",
+    "property_service_version_prop",
+    "soc_prop",
+    "socket_hook_prop",
+    "surfaceflinger_prop",
+    "telephony_config_prop",
+    "timezone_prop",
+    "vendor_socket_hook_prop",
+    "vndk_prop",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AppAccess {
+    Readable,
+    /// Reads by an untrusted app return nothing.
+    Denied,
+    /// No property_contexts entry labels the property.
+    Unknown,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PropertyContexts {
+    entries: Vec<Entry>,
+}
+
+impl PropertyContexts {
+    /// The device's own files, loaded once. Empty when none can be read.
+    pub fn system() -> &'static PropertyContexts {
+        static SYSTEM: OnceLock<PropertyContexts> = OnceLock::new();
+        SYSTEM.get_or_init(|| {
+            PropertyContexts::load(Path::new("/")).unwrap_or_else(|e| {
+                warn!("Cannot read property_contexts: {}", e);
+                PropertyContexts::default()
+            })
+        })
+    }
+
+    /// Every partition's file under `root`, which is `/` on a device or a
+    /// copied device tree. Missing files are skipped.
+    pub fn load(root: &Path) -> io::Result<PropertyContexts> {
+        let mut contexts = PropertyContexts::default();
+        for partition in Partition::ALL {
+            let path = partition.paths().iter().map(|path| root.join(path)).find(|path| path.is_file());
+            if let Some(path) = path {
+                contexts.add(partition, &std::fs::read_to_string(path)?)?;
+            }
+        }
+        Ok(contexts)
+    }
+
+    /// Adds the entries of one file. Like init, the first entry for a given
+    /// name and match type wins.
+    pub fn add(&mut self, partition: Partition, text: &str) -> io::Result<()> {
+        for entry in parse(partition, text)? {
+            let duplicate = self.entries.iter().any(|e| e.name == entry.name && e.match_type == entry.match_type);
+            if !duplicate {
+                self.entries.push(entry);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// The entry labelling `name`: an exact match, else the longest matching
+    /// prefix, else the `*` default.
+    pub fn lookup(&self, name: &str) -> Option<&Entry> {
+        let exact = self.entries.iter().find(|e| e.match_type == MatchType::Exact && e.name == name);
+        exact
+            .or_else(|| {
+                self.entries
+                    .iter()
+                    .filter(|e| e.match_type == MatchType::Prefix && e.name != "*" && e.matches(name))
+                    .max_by_key(|e| e.name.len())
+            })
+            .or_else(|| self.entries.iter().find(|e| e.name == "*"))
+    }
+
+    pub fn app_access(&self, name: &str) -> AppAccess {
+        match self.lookup(name) {
+            Some(entry) if APP_READABLE.contains(&entry.selinux_type()) => AppAccess::Readable,
+            Some(_) => AppAccess::Denied,
+            None => AppAccess::Unknown,
+        }
+    }
+
+    /// The context of every registry key that is a system property.
+    pub fn registry_report(&self) -> Vec<KeyContext> {
+        registry::KEYS.iter().filter(|key| registry::is_property(key)).map(|key| self.key_context(key)).collect()
+    }
+
+    pub fn key_context(&self, key: &str) -> KeyContext {
+        let entry = self.lookup(key);
+        KeyContext {
+            key: key.to_string(),
+            context: entry.map(|entry| entry.context.clone()),
+            partition: entry.map(|entry| entry.partition),
+            app_access: self.app_access(key),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct KeyContext {
+    pub key: String,
+    pub context: Option<String>,
+    /// The file the labelling entry came from.
+    pub partition: Option<Partition>,
+    pub app_access: AppAccess,
+}
+
+/// A property read that tells an unset property from a denied one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PropertyValue {
+    /// `PermissionDenied` when the property reads as unset and the policy
+    /// denies it to apps, so the empty read says nothing about the device.
+    pub state: IdState,
+    pub value: Option<String>,
+    pub context: Option<String>,
+}
+
+impl PropertyValue {
+    pub fn read(name: &str) -> PropertyValue {
+        PropertyValue::new(PropertyContexts::system(), name, props::get(name))
+    }
+
+    pub fn new(contexts: &PropertyContexts, name: &str, value: Option<String>) -> PropertyValue {
+        let state = match (&value, contexts.app_access(name)) {
+            (Some(_), _) => IdState::Available,
+            (None, AppAccess::Denied) => IdState::PermissionDenied,
+            (None, _) => IdState::Empty,
+        };
+        PropertyValue { state, value, context: contexts.lookup(name).map(|entry| entry.context.clone()) }
+    }
+}
+
+/// Parses one property_contexts file. Blank lines and `#` comments are skipped.
+pub fn parse(partition: Partition, text: &str) -> io::Result<Vec<Entry>> {
+    let mut entries = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let (name, context) = match fields.as_slice() {
+            [name, context, ..] => (name.to_string(), context.to_string()),
+            _ => return Err(invalid_data(format!("line {}: expected a name and a context: {:?}", number + 1, line))),
+        };
+        // user:role:type:level, where the MLS level may itself contain colons.
+        if context.split(':').count() < 4 {
+            return Err(invalid_data(format!("line {}: malformed context {:?}", number + 1, context)));
+        }
+        let match_type = match fields.get(2) {
+            None | Some(&"prefix") => MatchType::Prefix,
+            Some(&"exact") => MatchType::Exact,
+            Some(other) => {
+                return Err(invalid_data(format!("line {}: unknown match type {:?}", number + 1, other)));
+            }
+        };
+        let value_type = if fields.len() > 3 { Some(fields[3..].join(" ")) } else { None };
+        entries.push(Entry { name, context, match_type, value_type, partition });
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() -> PropertyContexts {
+        PropertyContexts::load(Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/selinux"))).unwrap()
+    }
+
+    #[test]
+    fn parses_match_and_value_types() {
+        let entries = parse(
+            Partition::Plat,
+            "# comment\n\nro.build.type u:object_r:build_prop:s0 exact enum eng user userdebug\n\
+             ril.  u:object_r:radio_prop:s0\n\
+             vendor.x u:object_r:vendor_x_prop:s0:c512,c768 prefix\n",
+        )
+        .unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].match_type, MatchType::Exact);
+        assert_eq!(entries[0].value_type.as_deref(), Some("enum eng user userdebug"));
+        assert_eq!(entries[0].selinux_type(), "build_prop");
+        assert_eq!((entries[1].match_type, entries[1].value_type.as_ref()), (MatchType::Prefix, None));
+        assert_eq!(entries[2].selinux_type(), "vendor_x_prop");
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        assert!(parse(Partition::Plat, "ro.build.\n").is_err());
+        assert!(parse(Partition::Plat, "ro.build. build_prop\n").is_err());
+        let error = parse(Partition::Plat, "# x\nro.x u:object_r:x:s0 fuzzy\n").unwrap_err();
+        assert_eq!(error.to_string(), "line 2: unknown match type \"fuzzy\"");
+    }
+
+    #[test]
+    fn loads_every_partition_from_a_tree() {
+        let contexts = fixture();
+        let partition = |name: &str| contexts.lookup(name).map(|entry| entry.partition);
+        assert_eq!(partition("ro.build.id"), Some(Partition::Plat));
+        assert_eq!(partition("persist.vendor.overlay.izat.optin"), Some(Partition::SystemExt));
+        assert_eq!(partition("persist.vendor.radio.atfwd.start"), Some(Partition::Vendor));
+        // The vendor file's persist.sys. loses to the platform's.
+        assert_eq!(contexts.lookup("persist.sys.kernel").unwrap().context, "u:object_r:system_prop:s0");
+    }
+
+    #[test]
+    fn exact_then_longest_prefix_then_default() {
+        let contexts = fixture();
+        let selinux_type = |name: &str| contexts.lookup(name).unwrap().selinux_type().to_string();
+        assert_eq!(selinux_type("persist.sys.timezone"), "timezone_prop");
+        // Exact entries do not label longer names.
+        assert_eq!(selinux_type("persist.sys.timezone2"), "system_prop");
+        assert_eq!(selinux_type("ro.runtime.firstboot"), "firstboot_prop");
+        assert_eq!(selinux_type("ro.runtime.other"), "system_prop");
+        assert_eq!(selinux_type("net.rmnet0.dns"), "net_radio_prop");
+        assert_eq!(selinux_type("qemu.hw.mainkeys"), "default_prop");
+        assert_eq!(PropertyContexts::default().lookup("ro.build.id"), None);
+    }
+
+    #[test]
+    fn predicts_app_access() {
+        let contexts = fixture();
+        assert_eq!(contexts.app_access("ro.build.version.sdk"), AppAccess::Readable);
+        assert_eq!(contexts.app_access("persist.sys.tz"), AppAccess::Readable);
+        assert_eq!(contexts.app_access("ro.serialno"), AppAccess::Denied);
+        assert_eq!(contexts.app_access("ril.subscription.types"), AppAccess::Denied);
+        assert_eq!(contexts.app_access("vendor.camera.aux.packagelist"), AppAccess::Denied);
+        assert_eq!(PropertyContexts::default().app_access("ro.build.id"), AppAccess::Unknown);
+
+        assert_eq!(PropertyValue::new(&contexts, "ro.serialno", None).state, IdState::PermissionDenied);
+        assert_eq!(PropertyValue::new(&contexts, "ro.build.id", None).state, IdState::Empty);
+        let value = PropertyValue::new(&contexts, "ro.serialno", Some("abc".into()));
+        assert_eq!((value.state, value.context.as_deref()), (IdState::Available, Some("u:object_r:serialno_prop:s0")));
+    }
+
+    #[test]
+    fn reports_every_registry_property() {
+        let report = fixture().registry_report();
+        assert!(report.iter().all(|key| registry::is_property(&key.key)));
+        let rild = report.iter().find(|key| key.key == "rild.libpath").unwrap();
+        assert_eq!(rild.context.as_deref(), Some("u:object_r:vendor_rild_prop:s0"));
+        assert_eq!(rild.app_access, AppAccess::Denied);
+        assert!(report.iter().all(|key| key.context.is_some()));
+    }
+}
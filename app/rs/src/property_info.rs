@@ -0,0 +1,272 @@
+/*
+ * The serialized property_info trie in /dev/__properties__ (Android 9+),
+ * which init builds from the property_contexts files and bionic uses to
+ * find the context, and so the prop area file, of every property.
+ */
+
+use std::collections::HashSet;
+use std::io;
+use std::path::Path;
+
+use crate::invalid_data;
+
+/// `PropertyInfoAreaHeader::current_version` this reader understands.
+pub const VERSION: u32 = 1;
+
+const NONE: u32 = !0;
+const HEADER_SIZE: usize = 24;
+/// Deeper than any dotted property name gets; bounds the recursion.
+const MAX_DEPTH: usize = 64;
+
+/// The context and type indexes a trie entry assigns, `None` when it leaves
+/// them to a shorter match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Indexes {
+    context: Option<u32>,
+    value_type: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Node {
+    /// One dot-separated segment of the property name.
+    name: String,
+    indexes: Indexes,
+    /// Sorted by name, as the serializer writes them.
+    children: Vec<Node>,
+    /// Partial final segments, longest first.
+    prefixes: Vec<(String, Indexes)>,
+    exact_matches: Vec<(String, Indexes)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertyInfo {
+    contexts: Vec<String>,
+    types: Vec<String>,
+    root: Node,
+}
+
+impl PropertyInfo {
+    pub fn read(path: &Path) -> io::Result<PropertyInfo> {
+        PropertyInfo::parse(&std::fs::read(path)?)
+    }
+
+    pub fn parse(bytes: &[u8]) -> io::Result<PropertyInfo> {
+        let area = Area { bytes };
+        if bytes.len() < HEADER_SIZE {
+            return Err(invalid_data(format!("property_info is {} bytes, shorter than its header", bytes.len())));
+        }
+        let minimum_supported_version = area.u32(4)?;
+        if minimum_supported_version > VERSION {
+            return Err(invalid_data(format!("property_info needs a version {} reader", minimum_supported_version)));
+        }
+        Ok(PropertyInfo {
+            contexts: area.strings(area.u32(12)?)?,
+            types: area.strings(area.u32(16)?)?,
+            root: area.node(area.u32(20)?, 0, &mut HashSet::new())?,
+        })
+    }
+
+    /// Every context, sorted; each has a prop area file of that name.
+    pub fn contexts(&self) -> &[String] {
+        &self.contexts
+    }
+
+    pub fn types(&self) -> &[String] {
+        &self.types
+    }
+
+    /// The context and value type of `name`, following bionic's
+    /// `GetPropertyInfoIndexes`: the deepest trie node on the name's path
+    /// and the prefixes along it, overridden by an exact match at the end.
+    pub fn lookup(&self, name: &str) -> (Option<&str>, Option<&str>) {
+        let mut found = Indexes { context: None, value_type: None };
+        let mut node = &self.root;
+        let mut remaining = name;
+        loop {
+            found = found.or(node.indexes);
+            found = found.or(prefix_match(node, remaining));
+            let (segment, rest) = match remaining.split_once('.') {
+                Some(split) => split,
+                None => break,
+            };
+            match node.children.binary_search_by(|child| child.name.as_str().cmp(segment)) {
+                Ok(i) => node = &node.children[i],
+                Err(_) => break,
+            }
+            remaining = rest;
+        }
+        match node.exact_matches.iter().find(|(exact, _)| exact == remaining) {
+            Some((_, indexes)) => found = found.or(*indexes),
+            None => found = found.or(prefix_match(node, remaining)),
+        }
+        (self.context(found.context), self.value_type(found.value_type))
+    }
+
+    fn context(&self, index: Option<u32>) -> Option<&str> {
+        self.contexts.get(index? as usize).map(String::as_str)
+    }
+
+    fn value_type(&self, index: Option<u32>) -> Option<&str> {
+        self.types.get(index? as usize).map(String::as_str)
+    }
+}
+
+impl Indexes {
+    /// `self` with the indexes `other` sets replacing its own.
+    fn or(self, other: Indexes) -> Indexes {
+        Indexes { context: other.context.or(self.context), value_type: other.value_type.or(self.value_type) }
+    }
+
+    fn none() -> Indexes {
+        Indexes { context: None, value_type: None }
+    }
+}
+
+fn prefix_match(node: &Node, remaining: &str) -> Indexes {
+    node.prefixes.iter().find(|(prefix, _)| remaining.starts_with(prefix.as_str())).map_or(Indexes::none(), |p| p.1)
+}
+
+/// Bounds-checked reads at offsets from the start of the file.
+struct Area<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Area<'a> {
+    fn u32(&self, offset: u32) -> io::Result<u32> {
+        self.field(offset, 0)
+    }
+
+    /// The `u32` `delta` bytes into the structure at `base`.
+    fn field(&self, base: u32, delta: u32) -> io::Result<u32> {
+        let offset = base.checked_add(delta).map(|offset| offset as usize);
+        let bytes = offset
+            .and_then(|offset| self.bytes.get(offset..offset + 4))
+            .ok_or_else(|| invalid_data(format!("offset {}+{} is past the end of property_info", base, delta)))?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn c_string(&self, offset: u32) -> io::Result<String> {
+        let tail = self
+            .bytes
+            .get(offset as usize..)
+            .ok_or_else(|| invalid_data(format!("string offset {} is past the end of property_info", offset)))?;
+        let len = tail
+            .iter()
+            .position(|b| *b == 0)
+            .ok_or_else(|| invalid_data(format!("unterminated string at offset {}", offset)))?;
+        Ok(String::from_utf8_lossy(&tail[..len]).into_owned())
+    }
+
+    /// A `u32` count followed by that many string offsets.
+    fn strings(&self, offset: u32) -> io::Result<Vec<String>> {
+        let offsets = self.offsets(offset.saturating_add(4), self.u32(offset)?)?;
+        offsets.into_iter().map(|offset| self.c_string(offset)).collect()
+    }
+
+    fn offsets(&self, offset: u32, count: u32) -> io::Result<Vec<u32>> {
+        if (count as usize).saturating_mul(4) > self.bytes.len() {
+            return Err(invalid_data(format!("array of {} entries does not fit in property_info", count)));
+        }
+        (0..count).map(|i| self.field(offset, i * 4)).collect()
+    }
+
+    /// A `PropertyEntry`: name offset, name length, context and type index.
+    fn entry(&self, offset: u32) -> io::Result<(String, Indexes)> {
+        let index = |delta| -> io::Result<Option<u32>> {
+            let index = self.field(offset, delta)?;
+            Ok(if index == NONE { None } else { Some(index) })
+        };
+        let indexes = Indexes { context: index(8)?, value_type: index(12)? };
+        Ok((self.c_string(self.u32(offset)?)?, indexes))
+    }
+
+    /// The entries of the count/offset pair `delta` bytes into the node at `base`.
+    fn entries(&self, base: u32, delta: u32) -> io::Result<Vec<(String, Indexes)>> {
+        let offsets = self.offsets(self.field(base, delta + 4)?, self.field(base, delta)?)?;
+        offsets.into_iter().map(|offset| self.entry(offset)).collect()
+    }
+
+    /// A `TrieNodeInternal`: its entry, then child, prefix and exact match
+    /// arrays as count/offset pairs. Each node is read once: an offset seen
+    /// before would be a cycle, or a shared subtree whose copies multiply.
+    fn node(&self, offset: u32, depth: usize, visited: &mut HashSet<u32>) -> io::Result<Node> {
+        if depth > MAX_DEPTH {
+            return Err(invalid_data(format!("property_info trie is deeper than {}", MAX_DEPTH)));
+        }
+        if !visited.insert(offset) {
+            return Err(invalid_data(format!("property_info trie revisits offset {}", offset)));
+        }
+        let (name, indexes) = self.entry(self.u32(offset)?)?;
+        let children = self.offsets(self.field(offset, 8)?, self.field(offset, 4)?)?;
+        Ok(Node {
+            name,
+            indexes,
+            children: children
+                .into_iter()
+                .map(|child| self.node(child, depth + 1, visited))
+                .collect::<io::Result<_>>()?,
+            prefixes: self.entries(offset, 12)?,
+            exact_matches: self.entries(offset, 20)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() -> PropertyInfo {
+        PropertyInfo::parse(include_bytes!("../tests/fixtures/dev_properties/property_info")).unwrap()
+    }
+
+    #[test]
+    fn reads_contexts_and_types() {
+        let info = fixture();
+        assert_eq!(info.contexts().len(), 6);
+        assert_eq!(info.contexts()[0], "u:object_r:build_prop:s0");
+        assert_eq!(info.types(), ["int", "string"]);
+    }
+
+    #[test]
+    fn looks_up_like_bionic() {
+        let info = fixture();
+        let context = |name: &str| info.lookup(name).0.unwrap().split(':').nth(2).unwrap().to_string();
+        // Exact match, with its own type.
+        assert_eq!(info.lookup("ro.build.version.sdk"), (Some("u:object_r:build_prop:s0"), Some("int")));
+        // Node context for `ro.build.`, inheriting the root's type.
+        assert_eq!(info.lookup("ro.build.id"), (Some("u:object_r:build_prop:s0"), Some("string")));
+        assert_eq!(context("ro.build.version.release"), "build_prop");
+        // A prefix that is not dot-terminated.
+        assert_eq!(context("ro.serialno"), "serialno_prop");
+        assert_eq!(context("net.rmnet0.dns1"), "net_radio_prop");
+        assert_eq!(context("net.hostname"), "system_prop");
+        assert_eq!(context("persist.sys.timezone"), "timezone_prop");
+        // Exact matches do not cover longer names.
+        assert_eq!(context("persist.sys.timezone2"), "system_prop");
+        assert_eq!(context("qemu.hw.mainkeys"), "default_prop");
+        assert_eq!(context("ro"), "default_prop");
+    }
+
+    #[test]
+    fn rejects_truncated_and_newer_files() {
+        let bytes = include_bytes!("../tests/fixtures/dev_properties/property_info");
+        assert!(PropertyInfo::parse(&bytes[..16]).is_err());
+        assert!(PropertyInfo::parse(&bytes[..bytes.len() / 2]).is_err());
+        let mut newer = bytes.to_vec();
+        newer[4] = 2;
+        assert_eq!(PropertyInfo::parse(&newer).unwrap_err().to_string(), "property_info needs a version 2 reader");
+    }
+
+    #[test]
+    fn rejects_repeated_nodes() {
+        let mut bytes = include_bytes!("../tests/fixtures/dev_properties/property_info").to_vec();
+        let root = u32::from_le_bytes([bytes[20], bytes[21], bytes[22], bytes[23]]) as usize;
+        // One child, read from the header's root offset: the root is its own child.
+        bytes[root + 4..root + 8].copy_from_slice(&1u32.to_le_bytes());
+        bytes[root + 8..root + 12].copy_from_slice(&20u32.to_le_bytes());
+        assert_eq!(
+            PropertyInfo::parse(&bytes).unwrap_err().to_string(),
+            format!("property_info trie revisits offset {}", root)
+        );
+    }
+}
@@ -0,0 +1,34 @@
+/*
+ * Read access to Android system properties (`getprop`).
+ */
+
+/// `__system_property_get(name)`, `None` when the property is unset or empty.
+/// Always `None` off Android.
+#[cfg(target_os = "android")]
+pub fn get(name: &str) -> Option<String> {
+    use std::ffi::{CStr, CString};
+    use std::os::raw::c_char;
+
+    let name = CString::new(name).ok()?;
+    let mut value = [0 as c_char; libc::PROP_VALUE_MAX as usize];
+    let len = unsafe { libc::__system_property_get(name.as_ptr(), value.as_mut_ptr()) };
+    if len <= 0 {
+        return None;
+    }
+    Some(unsafe { CStr::from_ptr(value.as_ptr()) }.to_string_lossy().into_owned())
+}
+
+#[cfg(not(target_os = "android"))]
+pub fn get(_name: &str) -> Option<String> {
+    None
+}
+
+/// A comma-separated property such as `ro.product.cpu.abilist`.
+pub fn get_list(name: &str) -> Vec<String> {
+    get(name).map_or_else(Vec::new, |value| split_list(&value))
+}
+
+/// The non-empty items of a comma-separated value.
+pub fn split_list(value: &str) -> Vec<String> {
+    value.split(',').filter(|item| !item.is_empty()).map(String::from).collect()
+}
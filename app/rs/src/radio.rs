@@ -0,0 +1,258 @@
+/*
+ * Radio, baseband and per-SIM operator information behind 基带, Build.RADIO,
+ * Build.getRadioVersion() and the rild properties.
+ */
+
+use jni::errors::{Error, Result};
+use jni::objects::JObject;
+use jni::JNIEnv;
+use serde::Serialize;
+
+use crate::context;
+use crate::frame;
+use crate::props;
+
+/// Slots the `persist.rild.nitz_*_ons_<n>` properties are numbered for.
+pub const NITZ_SLOTS: usize = 4;
+
+/// The properties the radio interface layer daemon publishes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RildProps {
+    /// `rild.libpath`, the vendor RIL implementation.
+    pub libpath: Option<String>,
+    /// `persist.rild.nitz_plmn`, the network that last sent a NITZ update.
+    pub nitz_plmn: Option<String>,
+    /// `persist.rild.nitz_long_ons_0` to `_3`.
+    pub nitz_long_ons: Vec<Option<String>>,
+    /// `persist.rild.nitz_short_ons_0` to `_3`.
+    pub nitz_short_ons: Vec<Option<String>>,
+    /// `ril.subscription.types`, e.g. `NV,RUIM`.
+    pub subscription_types: Vec<String>,
+}
+
+impl RildProps {
+    pub fn read() -> RildProps {
+        RildProps::from_props(props::get)
+    }
+
+    /// The rild properties as `get` reports them.
+    pub fn from_props(get: impl Fn(&str) -> Option<String>) -> RildProps {
+        let slots = |kind: &str| {
+            (0..NITZ_SLOTS).map(|slot| get(&format!("persist.rild.nitz_{}_ons_{}", kind, slot))).collect()
+        };
+        RildProps {
+            libpath: get("rild.libpath"),
+            nitz_plmn: get("persist.rild.nitz_plmn"),
+            nitz_long_ons: slots("long"),
+            nitz_short_ons: slots("short"),
+            subscription_types: get("ril.subscription.types").map_or_else(Vec::new, |value| props::split_list(&value)),
+        }
+    }
+}
+
+/// Whether the per-subscription list could be read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubscriptionAccess {
+    Available,
+    /// `getActiveSubscriptionInfoList` threw `SecurityException`: it needs
+    /// `READ_PHONE_STATE`. Only the default subscription is reported.
+    PermissionDenied,
+    /// Below API 24 there is no `createForSubscriptionId`; only the default
+    /// subscription is reported.
+    Unsupported,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SimSlot {
+    /// `SubscriptionInfo.getSimSlotIndex()`, `None` for the default subscription.
+    pub slot: Option<i32>,
+    pub subscription_id: Option<i32>,
+    pub carrier_name: Option<String>,
+    /// `TelephonyManager.getNetworkOperatorName()`, the registered network.
+    pub network_operator_name: Option<String>,
+    /// MCC+MNC of the registered network.
+    pub network_operator: Option<String>,
+    /// `TelephonyManager.getSimOperatorName()`, the SIM's home operator.
+    pub sim_operator_name: Option<String>,
+    pub sim_operator: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RadioInfo {
+    /// `Build.getRadioVersion()`.
+    pub radio_version: Option<String>,
+    /// `gsm.version.baseband`.
+    pub baseband: Option<String>,
+    /// `gsm.version.ril-impl`.
+    pub ril_impl: Option<String>,
+    /// `getActiveModemCount()` on API 30+, `getPhoneCount()` before.
+    pub phone_count: i32,
+    pub subscription_access: SubscriptionAccess,
+    pub sims: Vec<SimSlot>,
+    /// `gsm.operator.alpha`, one entry per slot, empty for a slot without one.
+    pub operator_alpha: Vec<String>,
+    pub rild: RildProps,
+}
+
+impl RadioInfo {
+    pub fn current(env: &JNIEnv) -> Result<RadioInfo> {
+        RadioInfo::from_context(env, context::application_context(env)?)
+    }
+
+    pub fn from_context<'a>(env: &JNIEnv<'a>, context: JObject<'a>) -> Result<RadioInfo> {
+        let sdk_int = context::sdk_int(env)?;
+        let telephony = context::system_service(env, context, "phone")?;
+        let phone_count = match sdk_int {
+            30.. => env.call_method(telephony, "getActiveModemCount", "()I", &[])?.i()?,
+            23.. => env.call_method(telephony, "getPhoneCount", "()I", &[])?.i()?,
+            _ => 1,
+        };
+        let (subscription_access, sims) = sims(env, context, telephony, sdk_int)?;
+        Ok(RadioInfo {
+            radio_version: radio_version(env)?,
+            baseband: baseband(),
+            ril_impl: props::get("gsm.version.ril-impl"),
+            phone_count,
+            subscription_access,
+            sims,
+            operator_alpha: props::get("gsm.operator.alpha").map_or_else(Vec::new, |value| per_slot(&value)),
+            rild: RildProps::read(),
+        })
+    }
+}
+
+/// `Build.getRadioVersion()`, `None` when the radio has not reported one yet.
+pub fn radio_version(env: &JNIEnv) -> Result<Option<String>> {
+    let value = env.call_static_method("android/os/Build", "getRadioVersion", "()Ljava/lang/String;", &[])?.l()?;
+    context::non_empty_string(env, value)
+}
+
+/// The deprecated `Build.RADIO` field, which reads `unknown` on API 14+.
+pub fn build_radio(env: &JNIEnv) -> Result<Option<String>> {
+    let value = env.get_static_field("android/os/Build", "RADIO", "Ljava/lang/String;")?.l()?;
+    context::non_empty_string(env, value)
+}
+
+/// `gsm.version.baseband`, the property `getRadioVersion` reads.
+pub fn baseband() -> Option<String> {
+    props::get("gsm.version.baseband")
+}
+
+/// A comma-separated per-slot property such as `gsm.operator.alpha`. Unlike
+/// `props::get_list`, empty entries are kept so each stays at its slot's index.
+pub fn per_slot(value: &str) -> Vec<String> {
+    value.split(',').map(String::from).collect()
+}
+
+fn sims<'a>(
+    env: &JNIEnv<'a>,
+    context: JObject<'a>,
+    telephony: JObject<'a>,
+    sdk_int: i32,
+) -> Result<(SubscriptionAccess, Vec<SimSlot>)> {
+    let default = || -> Result<Vec<SimSlot>> { Ok(vec![sim_slot(env, telephony, None, None, None)?]) };
+    if sdk_int < 24 {
+        return Ok((SubscriptionAccess::Unsupported, default()?));
+    }
+    let subscriptions = context::system_service(env, context, "telephony_subscription_service")?;
+    let list = match env.call_method(subscriptions, "getActiveSubscriptionInfoList", "()Ljava/util/List;", &[]) {
+        Ok(list) => list.l()?,
+        Err(Error::JavaException) if context::catch_exception(env, "java/lang/SecurityException")? => {
+            return Ok((SubscriptionAccess::PermissionDenied, default()?));
+        }
+        Err(e) => return Err(e),
+    };
+    // Null when no SIM is inserted.
+    if list.is_null() {
+        return Ok((SubscriptionAccess::Available, Vec::new()));
+    }
+
+    let mut sims = frame::map_list(env, list, |info| {
+        let slot = env.call_method(info, "getSimSlotIndex", "()I", &[])?.i()?;
+        let subscription_id = env.call_method(info, "getSubscriptionId", "()I", &[])?.i()?;
+        let carrier_name = env.call_method(info, "getCarrierName", "()Ljava/lang/CharSequence;", &[])?.l()?;
+        let carrier_name = char_sequence(env, carrier_name)?;
+        let manager = env
+            .call_method(
+                telephony,
+                "createForSubscriptionId",
+                "(I)Landroid/telephony/TelephonyManager;",
+                &[subscription_id.into()],
+            )?
+            .l()?;
+        sim_slot(env, manager, Some(slot), Some(subscription_id), carrier_name)
+    })?;
+    sims.sort_by_key(|sim| sim.slot);
+    Ok((SubscriptionAccess::Available, sims))
+}
+
+fn sim_slot<'a>(
+    env: &JNIEnv<'a>,
+    manager: JObject<'a>,
+    slot: Option<i32>,
+    subscription_id: Option<i32>,
+    carrier_name: Option<String>,
+) -> Result<SimSlot> {
+    let string = |name: &str| -> Result<Option<String>> {
+        context::non_empty_string(env, env.call_method(manager, name, "()Ljava/lang/String;", &[])?.l()?)
+    };
+    Ok(SimSlot {
+        slot,
+        subscription_id,
+        carrier_name,
+        network_operator_name: string("getNetworkOperatorName")?,
+        network_operator: string("getNetworkOperator")?,
+        sim_operator_name: string("getSimOperatorName")?,
+        sim_operator: string("getSimOperator")?,
+    })
+}
+
+fn char_sequence(env: &JNIEnv, value: JObject) -> Result<Option<String>> {
+    if value.is_null() {
+        return Ok(None);
+    }
+    context::non_empty_string(env, env.call_method(value, "toString", "()Ljava/lang/String;", &[])?.l()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn per_slot_keeps_empty_slots() {
+        assert_eq!(per_slot("CMCC,CHN-UNICOM"), ["CMCC", "CHN-UNICOM"]);
+        assert_eq!(per_slot(",Vodafone"), ["", "Vodafone"]);
+        assert_eq!(per_slot("Vodafone,"), ["Vodafone", ""]);
+        assert_eq!(per_slot("T-Mobile"), ["T-Mobile"]);
+    }
+
+    #[test]
+    fn reads_rild_props() {
+        let values: HashMap<&str, &str> = [
+            ("rild.libpath", "/vendor/lib64/libril-qc-hal-qmi.so"),
+            ("persist.rild.nitz_plmn", "46000"),
+            ("persist.rild.nitz_long_ons_0", "China Mobile"),
+            ("persist.rild.nitz_short_ons_0", "CMCC"),
+            ("persist.rild.nitz_long_ons_2", "China Unicom"),
+            ("ril.subscription.types", "NV,,RUIM"),
+        ]
+        .into_iter()
+        .collect();
+        let rild = RildProps::from_props(|name| values.get(name).map(|value| value.to_string()));
+        assert_eq!(rild.libpath.as_deref(), Some("/vendor/lib64/libril-qc-hal-qmi.so"));
+        assert_eq!(rild.nitz_plmn.as_deref(), Some("46000"));
+        assert_eq!(rild.nitz_long_ons, [Some("China Mobile".into()), None, Some("China Unicom".into()), None]);
+        assert_eq!(rild.nitz_short_ons, [Some("CMCC".into()), None, None, None]);
+        assert_eq!(rild.subscription_types, ["NV", "RUIM"]);
+    }
+
+    #[test]
+    fn unset_rild_props() {
+        let rild = RildProps::from_props(|_| None);
+        assert_eq!(rild.libpath, None);
+        assert_eq!(rild.nitz_long_ons, vec![None; NITZ_SLOTS]);
+        assert!(rild.subscription_types.is_empty());
+    }
+}
@@ -0,0 +1,314 @@
+/*
+ * The parameter registry: every key modifyParams handles, glob queries over
+ * their names backed by a prefix tree, and the setprop rules for the keys
+ * that are system properties.
+ */
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::error::Error;
+use std::fmt;
+use std::sync::OnceLock;
+
+use crate::prop_rules::{self, PropError, PropKind};
+use crate::props;
+
+/// Every key `modifyParams` has a handler for, in match order.
+pub const KEYS: [&str; crate::PARAMS.len()] = keys();
+
+const fn keys() -> [&'static str; crate::PARAMS.len()] {
+    let mut keys = [""; crate::PARAMS.len()];
+    let mut i = 0;
+    while i < keys.len() {
+        keys[i] = crate::PARAMS[i].0;
+        i += 1;
+    }
+    keys
+}
+
+/// A prefix tree over key names, one edge per character.
+#[derive(Debug, Default)]
+pub struct PrefixTree {
+    root: Node,
+}
+
+#[derive(Debug, Default)]
+struct Node {
+    children: BTreeMap<char, Node>,
+    terminal: bool,
+}
+
+impl PrefixTree {
+    pub fn new<'a, I: IntoIterator<Item = &'a str>>(keys: I) -> PrefixTree {
+        let mut tree = PrefixTree::default();
+        for key in keys {
+            tree.insert(key);
+        }
+        tree
+    }
+
+    pub fn insert(&mut self, key: &str) {
+        let mut node = &mut self.root;
+        for c in key.chars() {
+            node = node.children.entry(c).or_default();
+        }
+        node.terminal = true;
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.find(key).is_some_and(|node| node.terminal)
+    }
+
+    /// The keys starting with `prefix`, sorted.
+    pub fn with_prefix(&self, prefix: &str) -> Vec<String> {
+        let mut keys = BTreeSet::new();
+        if let Some(node) = self.find(prefix) {
+            let mut path = prefix.to_string();
+            collect(node, &mut path, &mut keys);
+        }
+        keys.into_iter().collect()
+    }
+
+    /// The keys matching a glob pattern, sorted. `*` matches any run of
+    /// characters, including none and including dots; `?` matches exactly
+    /// one. Everything else matches itself.
+    pub fn matching(&self, pattern: &str) -> Vec<String> {
+        let mut chars: Vec<char> = Vec::with_capacity(pattern.len());
+        for c in pattern.chars() {
+            // `**` matches what `*` does.
+            if !(c == '*' && chars.last() == Some(&'*')) {
+                chars.push(c);
+            }
+        }
+        let mut walk = Walk { pattern: &chars, seen: HashSet::new(), path: String::new(), keys: BTreeSet::new() };
+        walk.visit(&self.root, 0);
+        walk.keys.into_iter().collect()
+    }
+
+    fn find(&self, prefix: &str) -> Option<&Node> {
+        prefix.chars().try_fold(&self.root, |node, c| node.children.get(&c))
+    }
+}
+
+fn collect(node: &Node, path: &mut String, keys: &mut BTreeSet<String>) {
+    if node.terminal {
+        keys.insert(path.clone());
+    }
+    for (c, child) in &node.children {
+        path.push(*c);
+        collect(child, path, keys);
+        path.pop();
+    }
+}
+
+/// A glob match over the tree. Stars make the same node reachable at the
+/// same pattern position along many paths; as a node's path is fixed, each
+/// (node, position) pair is expanded once, bounding the walk by nodes times
+/// pattern length.
+struct Walk<'a> {
+    pattern: &'a [char],
+    seen: HashSet<(*const Node, usize)>,
+    path: String,
+    keys: BTreeSet<String>,
+}
+
+impl Walk<'_> {
+    fn visit(&mut self, node: &Node, index: usize) {
+        if !self.seen.insert((node, index)) {
+            return;
+        }
+        let first = match self.pattern.get(index) {
+            Some(first) => *first,
+            None => {
+                if node.terminal {
+                    self.keys.insert(self.path.clone());
+                }
+                return;
+            }
+        };
+        match first {
+            '*' => {
+                self.visit(node, index + 1);
+                for (c, child) in &node.children {
+                    self.descend(*c, child, index);
+                }
+            }
+            '?' => {
+                for (c, child) in &node.children {
+                    self.descend(*c, child, index + 1);
+                }
+            }
+            c => {
+                if let Some(child) = node.children.get(&c) {
+                    self.descend(c, child, index + 1);
+                }
+            }
+        }
+    }
+
+    fn descend(&mut self, c: char, child: &Node, index: usize) {
+        self.path.push(c);
+        self.visit(child, index);
+        self.path.pop();
+    }
+}
+
+/// The prefix tree over `KEYS`, built on first use.
+pub fn tree() -> &'static PrefixTree {
+    static TREE: OnceLock<PrefixTree> = OnceLock::new();
+    TREE.get_or_init(|| PrefixTree::new(KEYS))
+}
+
+/// The registry keys matching a glob pattern, sorted.
+pub fn matching(pattern: &str) -> Vec<String> {
+    tree().matching(pattern)
+}
+
+/// Whether a registry key names a system property, rather than a report, a
+/// `Build` field or a setting: a legal property name with a dot, outside `Build.`.
+pub fn is_property(key: &str) -> bool {
+    key.contains('.') && !key.starts_with("Build.") && prop_rules::check_name(key).is_ok()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SetError {
+    /// Not a registry key.
+    Unknown(String),
+    /// A registry key that is not a system property.
+    NotAProperty(String),
+    Rejected(PropError),
+}
+
+impl fmt::Display for SetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SetError::Unknown(key) => write!(f, "{} is not a registry key", key),
+            SetError::NotAProperty(key) => write!(f, "{} is not a system property", key),
+            SetError::Rejected(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for SetError {}
+
+impl From<PropError> for SetError {
+    fn from(e: PropError) -> SetError {
+        SetError::Rejected(e)
+    }
+}
+
+/// Validates setting registry key `key` to `value`, given its `current` value.
+pub fn check_set(key: &str, value: &str, current: Option<&str>) -> Result<PropKind, SetError> {
+    if !tree().contains(key) {
+        return Err(SetError::Unknown(key.to_string()));
+    }
+    if !is_property(key) {
+        return Err(SetError::NotAProperty(key.to_string()));
+    }
+    Ok(prop_rules::check_set(key, value, current)?)
+}
+
+/// `check_set` against the property's value on this device.
+pub fn check_set_current(key: &str, value: &str) -> Result<PropKind, SetError> {
+    check_set(key, value, props::get(key).as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree() -> PrefixTree {
+        PrefixTree::new([
+            "ro.build.version.sdk",
+            "ro.build.version.release",
+            "ro.build.version",
+            "ro.build.date",
+            "persist.vendor.cne.feature",
+            "persist.vendor.data.mode",
+            "persist.sys.tz",
+            "序列号",
+        ])
+    }
+
+    #[test]
+    fn prefix_and_exact_lookups() {
+        let tree = tree();
+        assert_eq!(tree.with_prefix("persist.vendor."), ["persist.vendor.cne.feature", "persist.vendor.data.mode"]);
+        assert_eq!(tree.with_prefix("ro.build.version").len(), 3);
+        assert!(tree.with_prefix("ro.product").is_empty());
+        assert!(tree.contains("ro.build.version"));
+        assert!(!tree.contains("ro.build.versio"));
+        assert!(tree.contains("序列号"));
+    }
+
+    #[test]
+    fn glob_patterns() {
+        let tree = tree();
+        assert_eq!(tree.matching("ro.build.version.*"), ["ro.build.version.release", "ro.build.version.sdk"]);
+        assert_eq!(tree.matching("persist.*.mode"), ["persist.vendor.data.mode"]);
+        assert_eq!(tree.matching("*.tz"), ["persist.sys.tz"]);
+        assert_eq!(tree.matching("ro.build.dat?"), ["ro.build.date"]);
+        assert_eq!(tree.matching("序?号"), ["序列号"]);
+        assert_eq!(tree.matching("ro.build.version"), ["ro.build.version"]);
+        assert_eq!(tree.matching("**").len(), 8);
+        assert!(tree.matching("ro.build.version.?").is_empty());
+    }
+
+    /// Every `"key" =>` arm of the modifyParams match is in `KEYS`, and
+    /// nothing else is.
+    #[test]
+    fn keys_are_unique() {
+        let mut seen = HashSet::new();
+        for key in KEYS {
+            assert!(seen.insert(key), "{} is registered twice", key);
+        }
+        assert_eq!(KEYS.len(), 253);
+        assert_eq!((KEYS[0], KEYS[252]), ("运行时间", "ro.build.stanv.ab"));
+    }
+
+    #[test]
+    fn property_keys_are_legal_names() {
+        let properties: Vec<&str> = KEYS.iter().copied().filter(|key| is_property(key)).collect();
+        assert!(properties.len() > 150);
+        for key in ["ro.build.date", "persist.rild.nitz_long_ons_0", "media.stagefright.enable-player"] {
+            assert!(properties.contains(&key), "{}", key);
+        }
+        for key in ["序列号", "Build.VERSION.SDK", "DEVICE_PROVISIONED", "uname -a", "Bootloader："] {
+            assert!(!is_property(key), "{}", key);
+        }
+    }
+
+    #[test]
+    fn set_checks_registry_membership_then_rules() {
+        assert_eq!(check_set("ro.build.id", "QKQ1", None).map(|kind| kind.read_only), Ok(true));
+        assert_eq!(check_set("ro.no.such.key", "1", None), Err(SetError::Unknown("ro.no.such.key".into())));
+        assert_eq!(check_set("Build.ID", "QKQ1", None), Err(SetError::NotAProperty("Build.ID".into())));
+        assert!(matches!(
+            check_set("ro.build.id", "QKQ1", Some("QKQ1")),
+            Err(SetError::Rejected(PropError::ReadOnly { .. }))
+        ));
+        assert!(matches!(
+            check_set("persist.sys.timezone", &"x".repeat(92), None),
+            Err(SetError::Rejected(PropError::ValueTooLong { len: 92, max: 91, .. }))
+        ));
+        assert_eq!(check_set_current("persist.sys.tz", "UTC").map(|kind| kind.persistent), Ok(true));
+    }
+
+    #[test]
+    fn registry_queries() {
+        assert!(matching("ro.build.version.*").contains(&"ro.build.version.sdk".to_string()));
+        assert!(matching("persist.vendor.*").iter().all(|key| key.starts_with("persist.vendor.")));
+        assert_eq!(matching("Build.VERSION.SDK*"), ["Build.VERSION.SDK", "Build.VERSION.SDK_INT"]);
+    }
+
+    #[test]
+    fn many_stars_stay_fast() {
+        // Backtracking without memoization tries every way of splitting each
+        // key among the stars, which does not finish on patterns like these.
+        let pattern = format!("{}z", "*?".repeat(24));
+        assert!(matching(&pattern).iter().all(|key| key.ends_with('z') && key.chars().count() >= 25));
+        assert_eq!(
+            matching("*.*.*.*.*.*"),
+            ["persist.vendor.bt.a2dp.hal.implementation", "use.voice.path.for.pcm.voip"]
+        );
+    }
+}
@@ -0,0 +1,169 @@
+/*
+ * Sensor inventory behind 传感器信息.
+ */
+
+use jni::errors::Result;
+use jni::objects::JObject;
+use jni::JNIEnv;
+use serde::Serialize;
+
+use crate::context;
+use crate::frame;
+
+/// `Sensor.TYPE_ALL`.
+const TYPE_ALL: i32 = -1;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Sensor {
+    pub name: String,
+    pub vendor: String,
+    #[serde(rename = "type")]
+    pub sensor_type: i32,
+    /// `android.sensor.accelerometer` and the like.
+    pub string_type: Option<String>,
+    pub resolution: f32,
+    /// Microseconds between events, 0 for on-change and one-shot sensors.
+    pub min_delay_us: i32,
+    pub reporting_mode: Option<i32>,
+    pub wake_up: Option<bool>,
+    /// Not exposed by the NDK; filled in from `android.hardware.Sensor`.
+    pub maximum_range: Option<f32>,
+    /// Milliamperes while in use. Not exposed by the NDK either.
+    pub power_ma: Option<f32>,
+}
+
+/// Enumerates the sensors through `ASensorManager`. Empty off Android.
+#[cfg(target_os = "android")]
+pub fn list() -> Vec<Sensor> {
+    use ndk_sys::{ASensorList, ASensorManager_getInstance, ASensorManager_getSensorList};
+
+    let mut sensors = Vec::new();
+    unsafe {
+        let manager = ASensorManager_getInstance();
+        if manager.is_null() {
+            warn!("ASensorManager_getInstance returned null");
+            return sensors;
+        }
+        let mut list: ASensorList = std::ptr::null();
+        let count = ASensorManager_getSensorList(manager, &mut list);
+        for i in 0..count.max(0) as usize {
+            let sensor = *list.add(i);
+            sensors.push(Sensor {
+                name: c_string(ndk_sys::ASensor_getName(sensor)).unwrap_or_default(),
+                vendor: c_string(ndk_sys::ASensor_getVendor(sensor)).unwrap_or_default(),
+                sensor_type: ndk_sys::ASensor_getType(sensor),
+                string_type: c_string(ndk_sys::ASensor_getStringType(sensor)),
+                resolution: ndk_sys::ASensor_getResolution(sensor),
+                min_delay_us: ndk_sys::ASensor_getMinDelay(sensor),
+                reporting_mode: Some(ndk_sys::ASensor_getReportingMode(sensor)),
+                wake_up: Some(ndk_sys::ASensor_isWakeUpSensor(sensor)),
+                maximum_range: None,
+                power_ma: None,
+            });
+        }
+    }
+    sensors
+}
+
+#[cfg(not(target_os = "android"))]
+pub fn list() -> Vec<Sensor> {
+    Vec::new()
+}
+
+/// `list()` with the maximum range and power taken from `SensorManager.getSensorList`.
+pub fn list_with_details(env: &JNIEnv) -> Result<Vec<Sensor>> {
+    let context = context::application_context(env)?;
+    Ok(merge(list(), java_sensors(env, context)?))
+}
+
+/// Fills in the Java-only fields of `sensors`, matching on name, vendor, type
+/// and wake-up flag, as a wake-up and a non-wake-up variant often share a name.
+/// Each NDK sensor takes at most one Java sensor; the unmatched are appended.
+fn merge(mut sensors: Vec<Sensor>, java_sensors: Vec<Sensor>) -> Vec<Sensor> {
+    for java_sensor in java_sensors {
+        match sensors.iter_mut().find(|sensor| {
+            sensor.maximum_range.is_none()
+                && sensor.name == java_sensor.name
+                && sensor.vendor == java_sensor.vendor
+                && sensor.sensor_type == java_sensor.sensor_type
+                && sensor.wake_up == java_sensor.wake_up
+        }) {
+            Some(sensor) => {
+                sensor.maximum_range = java_sensor.maximum_range;
+                sensor.power_ma = java_sensor.power_ma;
+            }
+            None => sensors.push(java_sensor),
+        }
+    }
+    sensors
+}
+
+/// The sensors as `SensorManager.getSensorList(Sensor.TYPE_ALL)` reports them.
+pub fn java_sensors<'a>(env: &JNIEnv<'a>, context: JObject<'a>) -> Result<Vec<Sensor>> {
+    let manager = context::system_service(env, context, "sensor")?;
+    let list = env.call_method(manager, "getSensorList", "(I)Ljava/util/List;", &[TYPE_ALL.into()])?.l()?;
+    frame::map_list(env, list, |sensor| {
+        let string = |name: &str| -> Result<Option<String>> {
+            context::optional_string(env, env.call_method(sensor, name, "()Ljava/lang/String;", &[])?.l()?)
+        };
+        Ok(Sensor {
+            name: string("getName")?.unwrap_or_default(),
+            vendor: string("getVendor")?.unwrap_or_default(),
+            sensor_type: env.call_method(sensor, "getType", "()I", &[])?.i()?,
+            string_type: string("getStringType")?,
+            resolution: env.call_method(sensor, "getResolution", "()F", &[])?.f()?,
+            min_delay_us: env.call_method(sensor, "getMinDelay", "()I", &[])?.i()?,
+            reporting_mode: Some(env.call_method(sensor, "getReportingMode", "()I", &[])?.i()?),
+            wake_up: Some(env.call_method(sensor, "isWakeUpSensor", "()Z", &[])?.z()?),
+            maximum_range: Some(env.call_method(sensor, "getMaximumRange", "()F", &[])?.f()?),
+            power_ma: Some(env.call_method(sensor, "getPower", "()F", &[])?.f()?),
+        })
+    })
+}
+
+#[cfg(target_os = "android")]
+unsafe fn c_string(ptr: *const std::os::raw::c_char) -> Option<String> {
+    if ptr.is_null() {
+        return None;
+    }
+    Some(std::ffi::CStr::from_ptr(ptr).to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sensor(wake_up: bool, maximum_range: Option<f32>) -> Sensor {
+        Sensor {
+            name: "BMI160 Accelerometer".into(),
+            vendor: "Bosch".into(),
+            sensor_type: 1,
+            string_type: Some("android.sensor.accelerometer".into()),
+            resolution: 0.0024,
+            min_delay_us: 5000,
+            reporting_mode: Some(0),
+            wake_up: Some(wake_up),
+            maximum_range,
+            power_ma: maximum_range.map(|_| 0.18),
+        }
+    }
+
+    #[test]
+    fn wake_up_variants_do_not_collide() {
+        let ndk = vec![sensor(false, None), sensor(true, None)];
+        let java = vec![sensor(true, Some(156.9)), sensor(false, Some(78.4))];
+        let merged = merge(ndk, java);
+        assert_eq!(merged.len(), 2);
+        assert_eq!((merged[0].wake_up, merged[0].maximum_range), (Some(false), Some(78.4)));
+        assert_eq!((merged[1].wake_up, merged[1].maximum_range), (Some(true), Some(156.9)));
+    }
+
+    #[test]
+    fn duplicates_match_once_and_extras_are_appended() {
+        let ndk = vec![sensor(false, None)];
+        let java = vec![sensor(false, Some(78.4)), sensor(false, Some(39.2))];
+        let merged = merge(ndk, java);
+        assert_eq!(merged.iter().map(|s| s.maximum_range).collect::<Vec<_>>(), [Some(78.4), Some(39.2)]);
+        assert!(merge(Vec::new(), vec![sensor(true, Some(1.0))])[0].power_ma.is_some());
+    }
+}
@@ -0,0 +1,125 @@
+/*
+ * Uptime and boot time behind 运行时间.
+ */
+
+use serde::Serialize;
+use std::io;
+use std::mem::MaybeUninit;
+
+use crate::time;
+
+pub const PROC_UPTIME_PATH: &str = "/proc/uptime";
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Runtime {
+    /// `SystemClock.elapsedRealtime()`: `CLOCK_BOOTTIME`, includes deep sleep.
+    pub elapsed_realtime_ms: i64,
+    /// `SystemClock.uptimeMillis()`: `CLOCK_MONOTONIC`, stops in deep sleep.
+    pub uptime_ms: i64,
+    /// First field of `/proc/uptime`, `None` when it cannot be read.
+    pub proc_uptime_secs: Option<f64>,
+    /// Second field of `/proc/uptime`, summed over all CPUs.
+    pub proc_idle_secs: Option<f64>,
+    /// Wall-clock time of boot in milliseconds since the epoch.
+    pub boot_time_ms: i64,
+    pub boot_time: String,
+}
+
+impl Runtime {
+    pub fn read() -> io::Result<Runtime> {
+        let elapsed_realtime_ms = clock_ms(libc::CLOCK_BOOTTIME)?;
+        let uptime_ms = clock_ms(libc::CLOCK_MONOTONIC)?;
+        let (proc_uptime_secs, proc_idle_secs) = match std::fs::read_to_string(PROC_UPTIME_PATH) {
+            Ok(text) => match parse_proc_uptime(&text) {
+                Some((uptime, idle)) => (Some(uptime), Some(idle)),
+                None => (None, None),
+            },
+            Err(e) => {
+                warn!("Failed to read {}: {}", PROC_UPTIME_PATH, e);
+                (None, None)
+            }
+        };
+        let boot_time_ms = time::now_ms() - elapsed_realtime_ms;
+        Ok(Runtime {
+            elapsed_realtime_ms,
+            uptime_ms,
+            proc_uptime_secs,
+            proc_idle_secs,
+            boot_time_ms,
+            boot_time: time::format_iso8601(boot_time_ms),
+        })
+    }
+
+    /// Time spent in deep sleep since boot.
+    pub fn deep_sleep_ms(&self) -> i64 {
+        self.elapsed_realtime_ms - self.uptime_ms
+    }
+}
+
+/// Parses `/proc/uptime`: `"<uptime> <idle>"` in seconds.
+pub fn parse_proc_uptime(text: &str) -> Option<(f64, f64)> {
+    let mut fields = text.split_whitespace();
+    let uptime = fields.next()?.parse().ok()?;
+    let idle = fields.next()?.parse().ok()?;
+    Some((uptime, idle))
+}
+
+// time_t and c_long are 32-bit on armeabi-v7a.
+#[allow(clippy::unnecessary_cast)]
+fn clock_ms(clock: libc::clockid_t) -> io::Result<i64> {
+    let mut ts = MaybeUninit::<libc::timespec>::uninit();
+    if unsafe { libc::clock_gettime(clock, ts.as_mut_ptr()) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let ts = unsafe { ts.assume_init() };
+    Ok(ts.tv_sec as i64 * 1000 + ts.tv_nsec as i64 / 1_000_000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runtime(elapsed_realtime_ms: i64, uptime_ms: i64) -> Runtime {
+        Runtime {
+            elapsed_realtime_ms,
+            uptime_ms,
+            proc_uptime_secs: None,
+            proc_idle_secs: None,
+            boot_time_ms: 0,
+            boot_time: time::format_iso8601(0),
+        }
+    }
+
+    #[test]
+    fn parses_proc_uptime() {
+        assert_eq!(parse_proc_uptime("350735.47 2234388.90\n"), Some((350735.47, 2234388.90)));
+        // Fresh boot, idle summed over 8 CPUs already past uptime.
+        assert_eq!(parse_proc_uptime("12.08 80.13"), Some((12.08, 80.13)));
+    }
+
+    #[test]
+    fn one_field_is_not_enough() {
+        assert_eq!(parse_proc_uptime("350735.47\n"), None);
+        assert_eq!(parse_proc_uptime(""), None);
+    }
+
+    #[test]
+    fn rejects_malformed_fields() {
+        assert_eq!(parse_proc_uptime("350735,47 2234388,90\n"), None);
+        assert_eq!(parse_proc_uptime("uptime idle\n"), None);
+        assert_eq!(parse_proc_uptime("350735.47 -\n"), None);
+    }
+
+    #[test]
+    fn deep_sleep_is_boottime_minus_monotonic() {
+        assert_eq!(runtime(90_000_000, 36_000_000).deep_sleep_ms(), 54_000_000);
+        assert_eq!(runtime(5_000, 5_000).deep_sleep_ms(), 0);
+    }
+
+    #[test]
+    fn reads_the_running_system() {
+        let runtime = Runtime::read().unwrap();
+        assert!(runtime.deep_sleep_ms() >= 0);
+        assert!(runtime.boot_time_ms > 0 && runtime.boot_time_ms <= time::now_ms());
+    }
+}
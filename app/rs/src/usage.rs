@@ -0,0 +1,183 @@
+/*
+ * Memory and storage usage behind 内存 已用/全部 and 储存 已用/全部.
+ */
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::io;
+use std::mem::MaybeUninit;
+use std::path::Path;
+
+use crate::invalid_data;
+
+pub const MEMINFO_PATH: &str = "/proc/meminfo";
+pub const DATA_PARTITION: &str = "/data";
+
+/// A used/total pair in bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Usage {
+    pub used: u64,
+    pub total: u64,
+}
+
+impl Usage {
+    pub fn available(&self) -> u64 {
+        self.total - self.used
+    }
+
+    pub fn used_percent(&self) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        self.used as f64 * 100.0 / self.total as f64
+    }
+}
+
+/// The `/proc/meminfo` fields, in bytes, keyed by name (`MemTotal`, `Cached`, ...).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MemInfo {
+    fields: HashMap<String, u64>,
+}
+
+impl MemInfo {
+    pub fn read() -> io::Result<MemInfo> {
+        MemInfo::parse(&std::fs::read_to_string(MEMINFO_PATH)?)
+    }
+
+    /// Parses `Name:   value kB` lines. Lines without a unit (`HugePages_Total`)
+    /// are counts and are kept as-is.
+    pub fn parse(text: &str) -> io::Result<MemInfo> {
+        let mut fields = HashMap::new();
+        for line in text.lines().filter(|line| !line.trim().is_empty()) {
+            let (name, rest) = line
+                .split_once(':')
+                .ok_or_else(|| invalid_data(format!("malformed meminfo line: {:?}", line)))?;
+            let mut parts = rest.split_whitespace();
+            let value: u64 = parts
+                .next()
+                .and_then(|value| value.parse().ok())
+                .ok_or_else(|| invalid_data(format!("malformed meminfo value: {:?}", line)))?;
+            let value = match parts.next() {
+                Some("kB") => value * 1024,
+                Some(unit) => return Err(invalid_data(format!("unknown meminfo unit: {:?}", unit))),
+                None => value,
+            };
+            fields.insert(name.trim().to_string(), value);
+        }
+        Ok(MemInfo { fields })
+    }
+
+    pub fn get(&self, name: &str) -> Option<u64> {
+        self.fields.get(name).copied()
+    }
+
+    pub fn total(&self) -> Option<u64> {
+        self.get("MemTotal")
+    }
+
+    /// `MemAvailable`, or the `MemFree + Buffers + Cached` estimate on kernels
+    /// older than 3.14 that do not report it.
+    pub fn available(&self) -> Option<u64> {
+        self.get("MemAvailable").or_else(|| {
+            Some(self.get("MemFree")? + self.get("Buffers").unwrap_or(0) + self.get("Cached").unwrap_or(0))
+        })
+    }
+
+    pub fn usage(&self) -> io::Result<Usage> {
+        let total = self.total().ok_or_else(|| invalid_data("MemTotal missing from meminfo".to_string()))?;
+        let available = self
+            .available()
+            .ok_or_else(|| invalid_data("MemAvailable missing from meminfo".to_string()))?;
+        Ok(Usage { used: total.saturating_sub(available), total })
+    }
+}
+
+pub fn memory_usage() -> io::Result<Usage> {
+    MemInfo::read()?.usage()
+}
+
+/// Usage of the filesystem holding `path`, as reported by `statvfs`.
+/// Reserved blocks count as used, matching `df`.
+pub fn storage_usage<P: AsRef<Path>>(path: P) -> io::Result<Usage> {
+    let path = CString::new(path.as_ref().to_string_lossy().into_owned())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    let mut stat = MaybeUninit::<libc::statvfs>::uninit();
+    if unsafe { libc::statvfs(path.as_ptr(), stat.as_mut_ptr()) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let stat = unsafe { stat.assume_init() };
+    // The statvfs fields are 32-bit on armeabi-v7a.
+    #[allow(clippy::unnecessary_cast)]
+    let (block_size, blocks, available_blocks) = (stat.f_frsize as u64, stat.f_blocks as u64, stat.f_bavail as u64);
+    let total = blocks * block_size;
+    let available = available_blocks * block_size;
+    Ok(Usage { used: total.saturating_sub(available), total })
+}
+
+pub fn data_storage_usage() -> io::Result<Usage> {
+    storage_usage(DATA_PARTITION)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GIB: u64 = 1024 * 1024 * 1024;
+
+    #[test]
+    fn parses_android_meminfo() {
+        let info = MemInfo::parse(include_str!("../tests/fixtures/meminfo_android.txt")).unwrap();
+        assert_eq!(info.total(), Some(7661512 * 1024));
+        assert_eq!(info.available(), Some(3012960 * 1024));
+        assert_eq!(info.get("CmaTotal"), Some(217088 * 1024));
+        assert_eq!(
+            info.usage().unwrap(),
+            Usage { used: (7661512 - 3012960) * 1024, total: 7661512 * 1024 }
+        );
+    }
+
+    #[test]
+    fn estimates_available_without_memavailable() {
+        let info = MemInfo::parse(include_str!("../tests/fixtures/meminfo_legacy.txt")).unwrap();
+        assert_eq!(info.get("MemAvailable"), None);
+        assert_eq!(info.available(), Some((101652 + 38472 + 612488) * 1024));
+    }
+
+    #[test]
+    fn missing_total_is_an_error() {
+        let info = MemInfo::parse(include_str!("../tests/fixtures/meminfo_truncated.txt")).unwrap();
+        assert_eq!(info.usage().unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn keeps_unitless_counts() {
+        let info = MemInfo::parse("HugePages_Total:       4\nHugepagesize:       2048 kB\n").unwrap();
+        assert_eq!(info.get("HugePages_Total"), Some(4));
+        assert_eq!(info.get("Hugepagesize"), Some(2 * 1024 * 1024));
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        assert!(MemInfo::parse("MemTotal 7661512 kB\n").is_err());
+        assert!(MemInfo::parse("MemTotal: lots kB\n").is_err());
+        assert!(MemInfo::parse("MemTotal: 7661512 MB\n").is_err());
+    }
+
+    #[test]
+    fn usage_percent() {
+        let usage = Usage { used: GIB, total: 4 * GIB };
+        assert_eq!(usage.available(), 3 * GIB);
+        assert_eq!(usage.used_percent(), 25.0);
+        assert_eq!(Usage { used: 0, total: 0 }.used_percent(), 0.0);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn reads_host_meminfo_and_root_filesystem() {
+        let memory = memory_usage().unwrap();
+        assert!(memory.total > 0 && memory.used <= memory.total);
+        let storage = storage_usage("/").unwrap();
+        assert!(storage.total > 0 && storage.used <= storage.total);
+    }
+}
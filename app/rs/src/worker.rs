@@ -0,0 +1,114 @@
+/*
+ * Background jobs on native threads attached to the stored JavaVM, reporting
+ * back to a Java `ReportListener`.
+ */
+
+use jni::errors::{Error, Result};
+use jni::objects::{GlobalRef, JObject};
+use jni::JNIEnv;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::thread;
+
+use crate::lifecycle;
+
+static NEXT_JOB: AtomicI32 = AtomicI32::new(1);
+
+/// A `com.windcloud.plugin.mac.utils.ReportListener`, held as a `GlobalRef`
+/// so it outlives the call that handed it over.
+pub struct Listener {
+    object: GlobalRef,
+}
+
+impl Listener {
+    pub fn new(env: &JNIEnv, object: JObject) -> Result<Listener> {
+        if object.is_null() {
+            return Err(Error::NullPtr("ReportListener"));
+        }
+        Ok(Listener { object: env.new_global_ref(object)? })
+    }
+
+    pub fn progress(&self, env: &JNIEnv, job: i32, param: &str, done: usize, total: usize) -> Result<()> {
+        let param = env.new_string(param)?;
+        env.call_method(
+            self.object.as_obj(),
+            "onProgress",
+            "(ILjava/lang/String;II)V",
+            &[job.into(), param.into(), (done as i32).into(), (total as i32).into()],
+        )?;
+        env.delete_local_ref(param.into())
+    }
+
+    pub fn result(&self, env: &JNIEnv, job: i32, json: &str) -> Result<()> {
+        let json = env.new_string(json)?;
+        env.call_method(self.object.as_obj(), "onResult", "(ILjava/lang/String;)V", &[job.into(), json.into()])?;
+        env.delete_local_ref(json.into())
+    }
+
+    pub fn error(&self, env: &JNIEnv, job: i32, message: &str) -> Result<()> {
+        let message = env.new_string(message)?;
+        env.call_method(self.object.as_obj(), "onError", "(ILjava/lang/String;)V", &[job.into(), message.into()])?;
+        env.delete_local_ref(message.into())
+    }
+}
+
+/// Handed to a running job so it can report progress.
+pub struct Progress<'a> {
+    env: &'a JNIEnv<'a>,
+    listener: &'a Listener,
+    job: i32,
+}
+
+impl<'a> Progress<'a> {
+    pub fn job(&self) -> i32 {
+        self.job
+    }
+
+    pub fn step(&self, param: &str, done: usize, total: usize) -> Result<()> {
+        self.listener.progress(self.env, self.job, param, done, total)
+    }
+}
+
+/// Runs `job` on a new thread attached to the JavaVM and delivers its JSON
+/// to `listener.onResult`, or its error to `listener.onError`. The thread
+/// detaches when the job returns. Returns the job id.
+pub fn spawn<F>(name: &str, listener: Listener, job: F) -> std::result::Result<i32, Box<dyn std::error::Error>>
+where
+    F: FnOnce(&JNIEnv, &Progress) -> std::result::Result<String, Box<dyn std::error::Error>> + Send + 'static,
+{
+    let vm = lifecycle::java_vm().ok_or("JavaVM, JNI_OnLoad has not run")?;
+    let id = NEXT_JOB.fetch_add(1, Ordering::Relaxed);
+    thread::Builder::new().name(format!("{}-{}", name, id)).spawn(move || {
+        // Detaches the thread when dropped at the end of the closure.
+        let env = match vm.attach_current_thread() {
+            Ok(env) => env,
+            Err(e) => {
+                error!("job {}: cannot attach to the JavaVM: {}", id, e);
+                return;
+            }
+        };
+        let outcome = job(&env, &Progress { env: &env, listener: &listener, job: id });
+        clear_exception(&env);
+        let delivered = match outcome {
+            Ok(json) => listener.result(&env, id, &json),
+            Err(e) => {
+                warn!("job {} failed: {}", id, e);
+                listener.error(&env, id, &e.to_string())
+            }
+        };
+        if let Err(e) = delivered {
+            warn!("job {}: listener failed: {}", id, e);
+            clear_exception(&env);
+        }
+        drop(listener);
+    })?;
+    Ok(id)
+}
+
+/// Logs and clears a pending exception: no Java frame on a worker thread
+/// would ever see it.
+fn clear_exception(env: &JNIEnv) {
+    if env.exception_check().unwrap_or(false) {
+        let _ = env.exception_describe();
+        let _ = env.exception_clear();
+    }
+}
@@ -0,0 +1,81 @@
+/*
+ * A desktop JVM for exercising the JNI exports without a device: the Android
+ * stand-ins and test listeners in tests/java and the app's real Java sources
+ * are compiled onto the class path, and libmodify_mac.so is put on the library
+ * path, so ModifyMacUtils loads it exactly as the app does.
+ */
+
+use jni::{InitArgsBuilder, JNIEnv, JNIVersion, JavaVM};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+
+pub const MODIFY_MAC_UTILS: &str = "com/windcloud/plugin/mac/utils/ModifyMacUtils";
+
+/// The process-wide JVM. A process can only ever create one.
+pub fn jvm() -> &'static JavaVM {
+    static JVM: OnceLock<JavaVM> = OnceLock::new();
+    JVM.get_or_init(|| {
+        let args = InitArgsBuilder::new()
+            .version(JNIVersion::V8)
+            .option(&format!("-Djava.class.path={}", compile_classes().display()))
+            .option(&format!("-Djava.library.path={}", library_dir().display()))
+            .option("-Xcheck:jni")
+            .build()
+            .unwrap();
+        JavaVM::new(args).unwrap()
+    })
+}
+
+/// Fails the test with the Java exception's description, if one is pending.
+pub fn assert_no_exception(env: &JNIEnv) {
+    if env.exception_check().unwrap() {
+        env.exception_describe().unwrap();
+        env.exception_clear().unwrap();
+        panic!("Java exception pending");
+    }
+}
+
+fn compile_classes() -> PathBuf {
+    let manifest = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let out = Path::new(env!("CARGO_TARGET_TMPDIR")).join("classes");
+    let mut sources = Vec::new();
+    java_sources(&manifest.join("tests/java"), &mut sources);
+    java_sources(&manifest.join("../src/main/java/com/windcloud/plugin/mac/utils"), &mut sources);
+
+    let javac = match std::env::var_os("JAVA_HOME") {
+        Some(home) => PathBuf::from(home).join("bin/javac"),
+        None => PathBuf::from("javac"),
+    };
+    let status = Command::new(&javac)
+        .arg("-d")
+        .arg(&out)
+        .args(&sources)
+        .status()
+        .unwrap_or_else(|e| panic!("cannot run {}: {}", javac.display(), e));
+    assert!(status.success(), "javac failed");
+    out
+}
+
+fn java_sources(dir: &Path, sources: &mut Vec<PathBuf>) {
+    for entry in dir.read_dir().unwrap().flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            java_sources(&path, sources);
+        } else if path.extension().is_some_and(|extension| extension == "java") {
+            sources.push(path);
+        }
+    }
+}
+
+/// Where cargo put libmodify_mac.so: next to this test binary in `deps`, or
+/// one level up.
+fn library_dir() -> PathBuf {
+    let exe = std::env::current_exe().unwrap();
+    let deps = exe.parent().unwrap();
+    let dir = [deps, deps.parent().unwrap()]
+        .into_iter()
+        .find(|dir| dir.join("libmodify_mac.so").is_file())
+        .expect("libmodify_mac.so not built");
+    dir.to_path_buf()
+}
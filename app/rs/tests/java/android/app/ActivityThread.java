@@ -0,0 +1,10 @@
+package android.app;
+
+/** Desktop stand-in: hands out the harness's single application. */
+public final class ActivityThread {
+    private static final Application APPLICATION = new Application();
+
+    public static Application currentApplication() {
+        return APPLICATION;
+    }
+}
@@ -0,0 +1,6 @@
+package android.app;
+
+import android.content.Context;
+
+public class Application extends Context {
+}
@@ -0,0 +1,32 @@
+package android.content;
+
+import android.content.pm.PackageManager;
+
+/** Desktop stand-in for the members the native library calls. */
+public class Context {
+    public static final String PACKAGE_NAME = "com.windcloud.plugin.mac";
+
+    private final PackageManager packageManager = new PackageManager();
+    private final String packageName;
+
+    public Context() {
+        this(PACKAGE_NAME);
+    }
+
+    /** A context for a package the PackageManager does not know. */
+    public Context(String packageName) {
+        this.packageName = packageName;
+    }
+
+    public PackageManager getPackageManager() {
+        return packageManager;
+    }
+
+    public String getPackageName() {
+        return packageName;
+    }
+
+    public Object getSystemService(String name) {
+        return null;
+    }
+}
@@ -0,0 +1,48 @@
+package android.content.pm;
+
+import android.content.Context;
+
+/** Knows exactly one package, the harness's own. */
+public class PackageManager {
+    public static final int GET_SIGNATURES = 0x40;
+    public static final int GET_PERMISSIONS = 0x1000;
+    public static final int GET_SIGNING_CERTIFICATES = 0x08000000;
+
+    /** 2022-02-15T08:30:00Z. */
+    public static final long FIRST_INSTALL_TIME = 1644913800000L;
+    /** 2022-03-01T00:00:00Z. */
+    public static final long LAST_UPDATE_TIME = 1646092800000L;
+    public static final byte[] CERTIFICATE = {0x30, (byte) 0x82, (byte) 0xff};
+
+    public static class NameNotFoundException extends Exception {
+        public NameNotFoundException(String name) {
+            super(name);
+        }
+    }
+
+    public PackageInfo getPackageInfo(String packageName, int flags) throws NameNotFoundException {
+        if (!Context.PACKAGE_NAME.equals(packageName)) {
+            throw new NameNotFoundException(packageName);
+        }
+        PackageInfo info = new PackageInfo();
+        info.packageName = packageName;
+        info.versionName = "1.0";
+        info.versionCode = 1;
+        info.firstInstallTime = FIRST_INSTALL_TIME;
+        info.lastUpdateTime = LAST_UPDATE_TIME;
+        info.applicationInfo = new ApplicationInfo();
+        info.applicationInfo.packageName = packageName;
+        info.applicationInfo.targetSdkVersion = 30;
+        Signature[] signers = {new Signature(CERTIFICATE)};
+        if ((flags & GET_SIGNATURES) != 0) {
+            info.signatures = signers;
+        }
+        if ((flags & GET_SIGNING_CERTIFICATES) != 0) {
+            info.signingInfo = new SigningInfo(signers);
+        }
+        if ((flags & GET_PERMISSIONS) != 0) {
+            info.requestedPermissions = new String[] {"android.permission.INTERNET"};
+        }
+        return info;
+    }
+}
@@ -0,0 +1,20 @@
+package android.content.pm;
+
+import java.util.Arrays;
+
+public class Signature {
+    private final byte[] signature;
+
+    public Signature(byte[] signature) {
+        this.signature = signature.clone();
+    }
+
+    public byte[] toByteArray() {
+        return signature.clone();
+    }
+
+    @Override
+    public int hashCode() {
+        return Arrays.hashCode(signature);
+    }
+}
@@ -0,0 +1,13 @@
+package android.content.pm;
+
+public class SigningInfo {
+    private final Signature[] signers;
+
+    public SigningInfo(Signature[] signers) {
+        this.signers = signers;
+    }
+
+    public Signature[] getApkContentsSigners() {
+        return signers;
+    }
+}
@@ -0,0 +1,8 @@
+package android.os;
+
+public class Build {
+    public static class VERSION {
+        /** Android 11, so the API 28+ members are looked up too. */
+        public static final int SDK_INT = 30;
+    }
+}
@@ -0,0 +1,48 @@
+package com.windcloud.plugin.mac.utils;
+
+import java.util.ArrayList;
+import java.util.List;
+import java.util.concurrent.CountDownLatch;
+import java.util.concurrent.TimeUnit;
+
+/** Test listener: records each callback and lets the test wait for the outcome. */
+public class RecordingReportListener implements ReportListener {
+    private final List<String> events = new ArrayList<>();
+    private final CountDownLatch finished = new CountDownLatch(1);
+    private volatile String outcome;
+
+    @Override
+    public synchronized void onProgress(int job, String param, int done, int total) {
+        events.add("progress " + job + " " + param + " " + done + "/" + total);
+    }
+
+    @Override
+    public void onResult(int job, String json) {
+        finish("result " + job, json);
+    }
+
+    @Override
+    public void onError(int job, String message) {
+        finish("error " + job, message);
+    }
+
+    /** Waits up to {@code millis} for onResult or onError; false on timeout. */
+    public boolean await(long millis) throws InterruptedException {
+        return finished.await(millis, TimeUnit.MILLISECONDS);
+    }
+
+    public synchronized String[] events() {
+        return events.toArray(new String[0]);
+    }
+
+    /** The JSON or error message of the finishing callback. */
+    public String outcome() {
+        return outcome;
+    }
+
+    private synchronized void finish(String event, String outcome) {
+        events.add(event);
+        this.outcome = outcome;
+        finished.countDown();
+    }
+}
@@ -0,0 +1,201 @@
+/*
+ * The JNI exports called through ModifyMacUtils on a desktop JVM.
+ */
+
+#![cfg(target_os = "linux")]
+
+mod harness;
+
+use harness::{assert_no_exception, jvm, MODIFY_MAC_UTILS};
+use jni::objects::{JObject, JString};
+
+#[test]
+fn init_takes_the_declared_int() {
+    let env = jvm().attach_current_thread().unwrap();
+    let result = env.call_static_method(MODIFY_MAC_UTILS, "init", "(I)I", &[7.into()]);
+    assert_no_exception(&env);
+    assert_eq!(result.unwrap().i().unwrap(), 1);
+}
+
+#[test]
+fn get_app_info_returns_the_signature_hash() {
+    let env = jvm().attach_current_thread().unwrap();
+    let context = env.new_object("android/content/Context", "()V", &[]).unwrap();
+    let result =
+        env.call_static_method(MODIFY_MAC_UTILS, "getAppInfo", "(Landroid/content/Context;)I", &[context.into()]);
+    assert_no_exception(&env);
+
+    let certificate = env
+        .get_static_field("android/content/pm/PackageManager", "CERTIFICATE", "[B")
+        .unwrap()
+        .l()
+        .unwrap();
+    let signature = env.new_object("android/content/pm/Signature", "([B)V", &[certificate.into()]).unwrap();
+    let expected = env.call_method(signature, "hashCode", "()I", &[]).unwrap().i().unwrap();
+    assert_eq!(result.unwrap().i().unwrap(), expected);
+}
+
+#[test]
+fn get_app_info_returns_an_error_value_instead_of_panicking() {
+    let env = jvm().attach_current_thread().unwrap();
+    let missing = env.new_string("com.example.missing").unwrap();
+    let context = env.new_object("android/content/Context", "(Ljava/lang/String;)V", &[missing.into()]).unwrap();
+    let result =
+        env.call_static_method(MODIFY_MAC_UTILS, "getAppInfo", "(Landroid/content/Context;)I", &[context.into()]);
+    // getPackageInfo threw NameNotFoundException; the export cleared it.
+    assert_no_exception(&env);
+    assert_eq!(result.unwrap().i().unwrap(), -1);
+}
+
+#[test]
+fn modify_params_reports_each_outcome() {
+    let env = jvm().attach_current_thread().unwrap();
+    let modify = |param: &str| {
+        let param = env.new_string(param).unwrap();
+        let result =
+            env.call_static_method(MODIFY_MAC_UTILS, "modifyParams", "(Ljava/lang/String;)I", &[param.into()]);
+        assert_no_exception(&env);
+        result.unwrap().i().unwrap()
+    };
+    assert_eq!(modify("运行时间"), 1);
+    assert_eq!(modify("Kernel版本"), 1);
+    // Registered but not implemented.
+    assert_eq!(modify("品牌"), 0);
+    assert_eq!(modify("no such parameter"), 0);
+}
+
+#[test]
+fn query_params_reads_the_current_application() {
+    let env = jvm().attach_current_thread().unwrap();
+    let query = |param: &str| -> Option<String> {
+        let param = env.new_string(param).unwrap();
+        let result = env
+            .call_static_method(
+                MODIFY_MAC_UTILS,
+                "queryParams",
+                "(Ljava/lang/String;)Ljava/lang/String;",
+                &[param.into()],
+            )
+            .unwrap()
+            .l()
+            .unwrap();
+        assert_no_exception(&env);
+        match result.is_null() {
+            true => None,
+            false => Some(env.get_string(JString::from(result)).unwrap().into()),
+        }
+    };
+    let times: serde_json::Value = serde_json::from_str(&query("App Install Time").unwrap()).unwrap();
+    assert_eq!(times["first_install"], "2022-02-15T08:30:00.000Z");
+    assert_eq!(times["last_update"], "2022-03-01T00:00:00.000Z");
+    assert_eq!(query("no such parameter"), None);
+}
+
+#[test]
+fn query_matching_returns_every_matching_key() {
+    let env = jvm().attach_current_thread().unwrap();
+    let query = |pattern: &str| -> serde_json::Value {
+        let pattern = env.new_string(pattern).unwrap();
+        let result = env
+            .call_static_method(
+                MODIFY_MAC_UTILS,
+                "queryMatching",
+                "(Ljava/lang/String;)Ljava/lang/String;",
+                &[pattern.into()],
+            )
+            .unwrap()
+            .l()
+            .unwrap();
+        assert_no_exception(&env);
+        let json: String = env.get_string(JString::from(result)).unwrap().into();
+        serde_json::from_str(&json).unwrap()
+    };
+    let times = query("App * Time");
+    let results = times["results"].as_object().unwrap();
+    assert_eq!(results.keys().collect::<Vec<_>>(), ["App Install Time", "App Last Update Time"]);
+    assert_eq!(results["App Last Update Time"]["first_install"], "2022-02-15T08:30:00.000Z");
+
+    // No properties or property_contexts off Android: every key is present and empty.
+    let versions = query("ro.build.version.*");
+    let empty = serde_json::json!({ "state": "empty", "value": null, "context": null });
+    assert_eq!(versions["results"]["ro.build.version.sdk"], empty);
+    assert!(versions["results"].as_object().unwrap().len() > 10);
+    assert!(versions["errors"].as_object().unwrap().is_empty());
+    assert!(query("no.such.*")["results"].as_object().unwrap().is_empty());
+}
+
+#[test]
+fn validate_param_explains_rejections() {
+    let env = jvm().attach_current_thread().unwrap();
+    let validate = |param: &str, value: &str| -> Option<String> {
+        let param = env.new_string(param).unwrap();
+        let value = env.new_string(value).unwrap();
+        let result = env
+            .call_static_method(
+                MODIFY_MAC_UTILS,
+                "validateParam",
+                "(Ljava/lang/String;Ljava/lang/String;)Ljava/lang/String;",
+                &[param.into(), value.into()],
+            )
+            .unwrap()
+            .l()
+            .unwrap();
+        assert_no_exception(&env);
+        match result.is_null() {
+            true => None,
+            false => Some(env.get_string(JString::from(result)).unwrap().into()),
+        }
+    };
+    assert_eq!(validate("persist.sys.timezone", "Asia/Shanghai"), None);
+    assert_eq!(validate("序列号", "1"), Some("序列号 is not a system property".into()));
+    assert_eq!(
+        validate("persist.sys.tz", &"x".repeat(92)),
+        Some("value of persist.sys.tz is 92 bytes; properties outside ro. hold at most 91 (PROP_VALUE_MAX 92)".into())
+    );
+}
+
+#[test]
+fn start_report_calls_the_listener() {
+    let env = jvm().attach_current_thread().unwrap();
+    let listener = env.new_object("com/windcloud/plugin/mac/utils/RecordingReportListener", "()V", &[]).unwrap();
+    let params = env.new_object_array(2, "java/lang/String", JObject::null()).unwrap();
+    for (i, param) in ["运行时间", "no such parameter"].into_iter().enumerate() {
+        env.set_object_array_element(params, i as i32, env.new_string(param).unwrap()).unwrap();
+    }
+    let job = env
+        .call_static_method(
+            MODIFY_MAC_UTILS,
+            "startReport",
+            "([Ljava/lang/String;Lcom/windcloud/plugin/mac/utils/ReportListener;)I",
+            &[params.into(), listener.into()],
+        )
+        .unwrap()
+        .i()
+        .unwrap();
+    assert_no_exception(&env);
+    assert!(job > 0);
+
+    let finished = env.call_method(listener, "await", "(J)Z", &[10_000i64.into()]).unwrap().z().unwrap();
+    assert!(finished, "no onResult or onError within 10s");
+    let events = env.call_method(listener, "events", "()[Ljava/lang/String;", &[]).unwrap().l().unwrap();
+    let events: Vec<String> = (0..env.get_array_length(events.into_inner()).unwrap())
+        .map(|i| {
+            let event = env.get_object_array_element(events.into_inner(), i).unwrap();
+            env.get_string(JString::from(event)).unwrap().into()
+        })
+        .collect();
+    assert_eq!(
+        events,
+        [
+            format!("progress {} 运行时间 1/2", job),
+            format!("progress {} no such parameter 2/2", job),
+            format!("result {}", job),
+        ]
+    );
+
+    let outcome = env.call_method(listener, "outcome", "()Ljava/lang/String;", &[]).unwrap().l().unwrap();
+    let json: String = env.get_string(JString::from(outcome)).unwrap().into();
+    let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert!(json["results"]["运行时间"]["elapsed_realtime_ms"].as_i64().unwrap() > 0);
+    assert_eq!(json["errors"]["no such parameter"], "no reporter");
+}
@@ -11,6 +11,42 @@ public class ModifyMacUtils {
 
     public static native int getAppInfo(Context content);
 
+    /**
+     * Logs the report behind {@code param}. Despite the name nothing is written
+     * to the device, so nothing here is checked against the property rules;
+     * see {@link #validateParam}.
+     */
     public static native int modifyParams(String param);
 
+    /**
+     * Returns the report behind {@code param} as JSON, or null when it has no reporter.
+     */
+    public static native String queryParams(String param);
+
+    /**
+     * Returns the registry keys matching a glob pattern ({@code *} and {@code ?})
+     * with their current values, as JSON {@code {"results": {...}, "errors": {...}}}.
+     */
+    public static native String queryMatching(String pattern);
+
+    /**
+     * Checks setting the system property behind {@code param} to {@code value}
+     * against the property service's rules. Returns null when allowed, otherwise
+     * why not: not a property, an illegal name, a value over PROP_VALUE_MAX, or
+     * an ro. property that is already set.
+     *
+     * <p>This is the only gate: no native method writes properties, so callers
+     * that do (setprop, or a root helper) must call this first with every value
+     * and refuse it on a non-null result. There is no profile model yet; a
+     * profile's entries have to be validated one by one the same way.
+     */
+    public static native String validateParam(String param, String value);
+
+    /**
+     * Runs the reports behind {@code params} on a native worker thread, or the
+     * full device report when {@code params} is null or empty. Returns the job
+     * id passed to {@code listener}, or -1 if the job could not be started.
+     */
+    public static native int startReport(String[] params, ReportListener listener);
+
 }
@@ -0,0 +1,18 @@
+package com.windcloud.plugin.mac.utils;
+
+/**
+ * Receives the progress and outcome of a report started with
+ * {@link ModifyMacUtils#startReport}. Called on the native worker thread.
+ */
+public interface ReportListener {
+
+    void onProgress(int job, String param, int done, int total);
+
+    /**
+     * {@code json} maps each parameter to its report under "results", and to
+     * the reason it has none under "errors".
+     */
+    void onResult(int job, String json);
+
+    void onError(int job, String message);
+}